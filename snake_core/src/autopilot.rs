@@ -0,0 +1,403 @@
+/* Computer player steering a snake towards food
+//
+// Paths are searched breadth-first over the board from the snake's head, so
+// the first food found is the nearest one, and the first step of the path to
+// it is the direction to turn to. Difficulties differ in how careful the
+// snake is about where that path leads:
+//
+//   Greedy    heads for the nearest food by the shortest path, and moves into
+//             any free cell when no food can be reached
+//   SafePath  takes the shortest path only if the cell it steps into leaves
+//             room for the whole snake, and moves into the largest open area
+//             otherwise
+//   Survival  also makes sure it can still reach its own tail after the step,
+//             and chases its tail while food cannot be reached safely
+//
+// Careful snakes also keep out of cells another snake's head could move into
+// on the same step, as heads meeting crash into each other.
+//
+// A search runs within a step of the game, so it indexes cells instead of
+// storing positions to keep to a few kilobytes of stack.
+*/
+
+use crate::board::{Board, Cell, Position, BOARD_HEIGHT, BOARD_WIDTH};
+use crate::snake::{Direction, Snake};
+
+const CELLS: usize = BOARD_HEIGHT * BOARD_WIDTH;
+// Directions searched, in order of preference between equally good ones
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+// Marks a cell a search did not reach
+const UNREACHED: u8 = u8::MAX;
+// Marks the cell a search starts from
+const START: u8 = u8::MAX - 1;
+
+/// How well the computer plays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    /// Goes straight for food, easily trapping itself
+    Greedy,
+    /// Goes for food unless that leaves too little room to move
+    SafePath,
+    /// Keeps a way out to its own tail at all times
+    Survival,
+}
+
+/// Difficulties from the easiest to the hardest
+pub const DIFFICULTIES: [Difficulty; 3] = [
+    Difficulty::Greedy,
+    Difficulty::SafePath,
+    Difficulty::Survival,
+];
+
+impl Difficulty {
+    /// Name shown to the player
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Greedy => "GREEDY",
+            Difficulty::SafePath => "SAFE PATH",
+            Difficulty::Survival => "SURVIVAL",
+        }
+    }
+}
+
+/// Choose a direction for the snake to turn to on its next step, or None to let it carry on
+pub fn choose_direction(board: &Board, snake: &Snake, difficulty: Difficulty) -> Option<Direction> {
+    let head = snake.head();
+    let search = Search::new(board, head);
+    let to_food = search
+        .cells()
+        .find(|position| matches!(board.get(*position), Cell::Food(_)))
+        .and_then(|food| search.first_step(food));
+    match difficulty {
+        Difficulty::Greedy => to_food.or_else(|| {
+            DIRECTIONS
+                .iter()
+                .copied()
+                .find(|direction| free_neighbour(board, head, *direction).is_some())
+        }),
+        Difficulty::SafePath => to_food
+            .filter(|direction| {
+                !is_contested(board, snake, *direction)
+                    && room_after(board, snake, *direction) >= snake.length()
+            })
+            .or_else(|| roomiest_direction(board, snake)),
+        Difficulty::Survival => to_food
+            .filter(|direction| {
+                !is_contested(board, snake, *direction)
+                    && room_after(board, snake, *direction) >= snake.length()
+                    && tail_reachable_after(board, snake, *direction)
+            })
+            .or_else(|| {
+                // Following the tail keeps the snake alive until food comes within reach
+                let tail = snake.tail();
+                search
+                    .cells()
+                    .find(|position| is_next_to(board, *position, tail))
+                    .and_then(|position| search.first_step(position))
+                    .filter(|direction| !is_contested(board, snake, *direction))
+            })
+            .or_else(|| roomiest_direction(board, snake)),
+    }
+}
+
+/// Check if a snake can move into the cell at given position
+fn is_free(board: &Board, position: Position) -> bool {
+    match board.get(position) {
+        Cell::Empty | Cell::Food(_) => true,
+        Cell::Wall | Cell::Head(_) | Cell::Body(_) => false,
+    }
+}
+
+/// Cell next to given position in given direction, if a snake can move into it
+fn free_neighbour(board: &Board, position: Position, direction: Direction) -> Option<Position> {
+    board
+        .neighbour(position, direction)
+        .filter(|neighbour| is_free(board, *neighbour))
+}
+
+/// Check if two cells are next to each other
+fn is_next_to(board: &Board, position: Position, other: Position) -> bool {
+    DIRECTIONS
+        .iter()
+        .any(|direction| board.neighbour(position, *direction) == Some(other))
+}
+
+/// Check if another snake's head could move into the cell the snake steps into in given direction
+fn is_contested(board: &Board, snake: &Snake, direction: Direction) -> bool {
+    let head = snake.head();
+    match board.neighbour(head, direction) {
+        Some(position) => DIRECTIONS.iter().any(|side| {
+            board
+                .neighbour(position, *side)
+                .filter(|neighbour| *neighbour != head)
+                .map_or(false, |neighbour| {
+                    matches!(board.get(neighbour), Cell::Head(_))
+                })
+        }),
+        None => false,
+    }
+}
+
+/// Number of free cells the snake can get to after stepping in given direction, including the one stepped into
+fn room_after(board: &Board, snake: &Snake, direction: Direction) -> usize {
+    match free_neighbour(board, snake.head(), direction) {
+        Some(position) => Search::new(board, position).reached + 1,
+        None => 0,
+    }
+}
+
+/// Check if the snake can still get next to its tail after stepping in given direction
+fn tail_reachable_after(board: &Board, snake: &Snake, direction: Direction) -> bool {
+    let tail = snake.tail();
+    match free_neighbour(board, snake.head(), direction) {
+        Some(position) => {
+            is_next_to(board, position, tail)
+                || Search::new(board, position)
+                    .cells()
+                    .any(|reached| is_next_to(board, reached, tail))
+        }
+        None => false,
+    }
+}
+
+/// Direction leading into the largest area of free cells, preferring cells no other head could move into.
+/// None if the snake is boxed in.
+fn roomiest_direction(board: &Board, snake: &Snake) -> Option<Direction> {
+    let mut best = None;
+    let mut best_rank = (false, 0);
+    for direction in DIRECTIONS.iter() {
+        let room = room_after(board, snake, *direction);
+        let rank = (!is_contested(board, snake, *direction), room);
+        if room > 0 && (best.is_none() || rank > best_rank) {
+            best = Some(*direction);
+            best_rank = rank;
+        }
+    }
+    best
+}
+
+/// Index of the cell at given position in a search
+fn index(position: Position) -> usize {
+    position.row * BOARD_WIDTH + position.col
+}
+
+/// Position of the cell at given index in a search
+fn position(index: usize) -> Position {
+    Position {
+        row: index / BOARD_WIDTH,
+        col: index % BOARD_WIDTH,
+    }
+}
+
+/// Breadth-first search over the free cells reachable from a start position
+struct Search {
+    /// Index in DIRECTIONS of the first step of a shortest path to each cell, or UNREACHED or START
+    first_steps: [u8; CELLS],
+    /// Indices of cells reached, nearest first
+    order: [u16; CELLS],
+    /// Number of cells reached, not counting the start
+    reached: usize,
+}
+
+impl Search {
+    /// Search from given position, which does not need to be free itself
+    fn new(board: &Board, start: Position) -> Self {
+        let mut search = Search {
+            first_steps: [UNREACHED; CELLS],
+            order: [0; CELLS],
+            reached: 0,
+        };
+        search.first_steps[index(start)] = START;
+        let mut current = start;
+        let mut next = 0;
+        loop {
+            let first_step = search.first_steps[index(current)];
+            for (step, direction) in DIRECTIONS.iter().enumerate() {
+                let neighbour = match free_neighbour(board, current, *direction) {
+                    Some(neighbour) => index(neighbour),
+                    None => continue,
+                };
+                if search.first_steps[neighbour] != UNREACHED {
+                    continue;
+                }
+                search.first_steps[neighbour] = if first_step == START {
+                    step as u8
+                } else {
+                    first_step
+                };
+                search.order[search.reached] = neighbour as u16;
+                search.reached += 1;
+            }
+            if next == search.reached {
+                return search;
+            }
+            current = position(search.order[next] as usize);
+            next += 1;
+        }
+    }
+
+    /// First step of a shortest path from the start to given position, None if the search did not reach it
+    fn first_step(&self, position: Position) -> Option<Direction> {
+        DIRECTIONS
+            .get(self.first_steps[index(position)] as usize)
+            .copied()
+    }
+
+    /// Positions of cells reached, nearest first
+    fn cells<'s>(&'s self) -> impl Iterator<Item = Position> + 's {
+        self.order[..self.reached]
+            .iter()
+            .map(|index| position(*index as usize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::food::FoodKind;
+    use crate::level::Wall;
+    use crate::prng::{Pcg32, Rng};
+    use crate::snake::{Move, MAX_SNAKE_SIZE};
+
+    /// Put regular food on a random empty cell
+    fn place_food(board: &mut Board, rng: &mut Pcg32) {
+        let index = rng.gen_range(board.empty_cells() as u64) as usize;
+        let position = board.nth_empty_cell(index).unwrap();
+        board.set(position, Cell::Food(FoodKind::Regular));
+    }
+
+    /// Let the computer play alone for given number of steps. Return food eaten, and whether the snake survived.
+    fn play(difficulty: Difficulty, steps: usize) -> (usize, bool) {
+        let mut buffer = [Position::default(); MAX_SNAKE_SIZE];
+        let mut board = Board::default();
+        let head = Position { row: 11, col: 39 };
+        let mut snake = Snake::new(&mut buffer, head, Direction::Left, 3, &mut board);
+        let mut rng = Pcg32::new(1);
+        place_food(&mut board, &mut rng);
+        let mut eaten = 0;
+        for _ in 0..steps {
+            if let Some(direction) = choose_direction(&board, &snake, difficulty) {
+                snake.set_turn_direction(direction);
+            }
+            match snake.tick(&mut board) {
+                Move::Moved => (),
+                Move::Ate(_) => {
+                    eaten += 1;
+                    place_food(&mut board, &mut rng);
+                }
+                Move::Collided => return (eaten, false),
+            }
+        }
+        (eaten, true)
+    }
+
+    #[test]
+    fn shortest_path_leads_to_food() {
+        let mut buffer = [Position::default(); 8];
+        let mut board = Board::default();
+        let head = Position { row: 10, col: 10 };
+        let snake = Snake::new(&mut buffer, head, Direction::Left, 3, &mut board);
+        board.set(Position { row: 4, col: 10 }, Cell::Food(FoodKind::Regular));
+        for difficulty in DIFFICULTIES.iter() {
+            assert_eq!(
+                choose_direction(&board, &snake, *difficulty),
+                Some(Direction::Up)
+            );
+        }
+        // Wall right above the head is shorter to go round on the left
+        let wall = Wall {
+            top_left: Position { row: 9, col: 8 },
+            height: 1,
+            width: 13,
+        };
+        for col in wall.top_left.col..(wall.top_left.col + wall.width) {
+            board.set(Position { row: 9, col }, Cell::Wall);
+        }
+        assert_eq!(
+            choose_direction(&board, &snake, Difficulty::Greedy),
+            Some(Direction::Left)
+        );
+    }
+
+    #[test]
+    fn careful_snakes_stay_out_of_dead_ends() {
+        let mut buffer = [Position::default(); 16];
+        let mut board = Board::default();
+        // Box of 3x3 cells with an opening on its right, too small for the snake
+        let walls = [
+            Wall {
+                top_left: Position { row: 1, col: 1 },
+                height: 1,
+                width: 5,
+            },
+            Wall {
+                top_left: Position { row: 5, col: 1 },
+                height: 1,
+                width: 5,
+            },
+            Wall {
+                top_left: Position { row: 1, col: 1 },
+                height: 5,
+                width: 1,
+            },
+            Wall {
+                top_left: Position { row: 1, col: 5 },
+                height: 2,
+                width: 1,
+            },
+            Wall {
+                top_left: Position { row: 4, col: 5 },
+                height: 2,
+                width: 1,
+            },
+        ];
+        for wall in walls.iter() {
+            for row in wall.top_left.row..(wall.top_left.row + wall.height) {
+                for col in wall.top_left.col..(wall.top_left.col + wall.width) {
+                    board.set(Position { row, col }, Cell::Wall);
+                }
+            }
+        }
+        board.set(Position { row: 3, col: 4 }, Cell::Food(FoodKind::Regular));
+        let head = Position { row: 3, col: 6 };
+        let snake = Snake::new(&mut buffer, head, Direction::Up, 12, &mut board);
+        assert_eq!(
+            choose_direction(&board, &snake, Difficulty::Greedy),
+            Some(Direction::Left)
+        );
+        for difficulty in [Difficulty::SafePath, Difficulty::Survival].iter() {
+            let direction = choose_direction(&board, &snake, *difficulty);
+            assert!(direction.is_some());
+            assert_ne!(direction, Some(Direction::Left));
+        }
+    }
+
+    #[test]
+    fn boxed_in_snake_has_nowhere_to_go() {
+        let mut buffer = [Position::default(); 8];
+        let mut board = Board::default();
+        let head = Position { row: 0, col: 0 };
+        let snake = Snake::new(&mut buffer, head, Direction::Up, 3, &mut board);
+        board.set(Position { row: 0, col: 1 }, Cell::Wall);
+        for difficulty in DIFFICULTIES.iter() {
+            assert_eq!(choose_direction(&board, &snake, *difficulty), None);
+        }
+    }
+
+    #[test]
+    fn computer_eats_food_on_its_own() {
+        for difficulty in DIFFICULTIES.iter() {
+            let (eaten, survived) = play(*difficulty, 1000);
+            assert!(eaten >= 20, "{:?} ate {}", difficulty, eaten);
+            // Careful snakes do not run into themselves on an open board
+            if *difficulty != Difficulty::Greedy {
+                assert!(survived, "{:?}", difficulty);
+            }
+        }
+    }
+}
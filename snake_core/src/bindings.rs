@@ -0,0 +1,366 @@
+/* Key bindings of game actions
+//
+// Every action is bound to exactly one key, and no key to more than one
+// action. Keys are named apart from any keyboard driver, so that bindings can
+// be tested on the host and kept on disk along with other settings.
+//
+// Layout: one byte per action, in the order of ACTIONS. Arrow keys take the
+// bytes from 0x80 on, any other key its ASCII code.
+*/
+
+use core::fmt;
+
+use crate::snake::Direction;
+
+/// Number of actions keys are bound to
+pub const ACTION_COUNT: usize = 11;
+/// Bytes taken by encoded bindings
+pub const ENCODED_SIZE: usize = ACTION_COUNT;
+/// Actions in the order they are listed and stored
+pub const ACTIONS: [Action; ACTION_COUNT] = [
+    Action::Turn(0, Direction::Up),
+    Action::Turn(0, Direction::Down),
+    Action::Turn(0, Direction::Left),
+    Action::Turn(0, Direction::Right),
+    Action::Turn(1, Direction::Up),
+    Action::Turn(1, Direction::Down),
+    Action::Turn(1, Direction::Left),
+    Action::Turn(1, Direction::Right),
+    Action::Pause,
+    Action::Restart,
+    Action::Quit,
+];
+/// Presets in the order they are cycled through
+pub const PRESETS: [Preset; 3] = [Preset::Arrows, Preset::Wasd, Preset::Hjkl];
+// Codes of arrow keys
+const UP_CODE: u8 = 0x80;
+const DOWN_CODE: u8 = 0x81;
+const LEFT_CODE: u8 = 0x82;
+const RIGHT_CODE: u8 = 0x83;
+// Characters of keys without a printable character
+const BACKSPACE: u8 = 0x08;
+const ENTER: u8 = b'\n';
+const ESCAPE: u8 = 0x1b;
+
+/// Key on the keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// Key typing an ASCII character, letters in upper case
+    Char(u8),
+}
+
+impl Key {
+    /// Enter key, confirming choices in menus
+    pub const ENTER: Key = Key::Char(ENTER);
+    /// Escape key, going back from menus
+    pub const ESCAPE: Key = Key::Char(ESCAPE);
+    /// Backspace key, deleting the last letter of initials
+    pub const BACKSPACE: Key = Key::Char(BACKSPACE);
+
+    /// Get the key typing given character, if it is printable ASCII, Enter, Escape or Backspace
+    pub fn from_char(character: char) -> Option<Self> {
+        match character {
+            '\u{8}' | '\n' | '\u{1b}' => Some(Key::Char(character as u8)),
+            ' ' => Some(Key::Char(b' ')),
+            _ if character.is_ascii_graphic() => {
+                Some(Key::Char(character.to_ascii_uppercase() as u8))
+            }
+            _ => None,
+        }
+    }
+
+    /// Check if the key is kept for menus and typing initials, Enter, Escape and Backspace, so that it cannot be bound
+    pub fn is_reserved(self) -> bool {
+        self == Key::ENTER || self == Key::ESCAPE || self == Key::BACKSPACE
+    }
+
+    /// Byte the key is stored as
+    fn code(self) -> u8 {
+        match self {
+            Key::Up => UP_CODE,
+            Key::Down => DOWN_CODE,
+            Key::Left => LEFT_CODE,
+            Key::Right => RIGHT_CODE,
+            Key::Char(character) => character,
+        }
+    }
+
+    /// Get the key stored as given byte
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            UP_CODE => Some(Key::Up),
+            DOWN_CODE => Some(Key::Down),
+            LEFT_CODE => Some(Key::Left),
+            RIGHT_CODE => Some(Key::Right),
+            _ => Self::from_char(code as char).filter(|key| key.code() == code),
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Bytes of a key typing a character, for its name
+        let character = [match *self {
+            Key::Char(character) => character,
+            _ => 0,
+        }];
+        // Padded, so that names line up in menus
+        f.pad(match *self {
+            Key::Up => "UP",
+            Key::Down => "DOWN",
+            Key::Left => "LEFT",
+            Key::Right => "RIGHT",
+            Key::Char(b' ') => "SPACE",
+            Key::Char(BACKSPACE) => "BACKSPACE",
+            Key::Char(ENTER) => "ENTER",
+            Key::Char(ESCAPE) => "ESC",
+            Key::Char(_) => core::str::from_utf8(&character).unwrap_or("?"),
+        })
+    }
+}
+
+/// Something a key can be bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Turn the snake of given player
+    Turn(usize, Direction),
+    Pause,
+    /// Start a new game, or choose the selected item of a menu
+    Restart,
+    /// Leave the game for the title screen
+    Quit,
+}
+
+impl Action {
+    /// Name shown in the controls menu
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Turn(0, Direction::Up) => "1P UP",
+            Action::Turn(0, Direction::Down) => "1P DOWN",
+            Action::Turn(0, Direction::Left) => "1P LEFT",
+            Action::Turn(0, Direction::Right) => "1P RIGHT",
+            Action::Turn(_, Direction::Up) => "2P UP",
+            Action::Turn(_, Direction::Down) => "2P DOWN",
+            Action::Turn(_, Direction::Left) => "2P LEFT",
+            Action::Turn(_, Direction::Right) => "2P RIGHT",
+            Action::Pause => "PAUSE",
+            Action::Restart => "RESTART",
+            Action::Quit => "QUIT",
+        }
+    }
+}
+
+/// Set of bindings for the first player's turns, the second player taking keys left free
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    Arrows,
+    Wasd,
+    Hjkl,
+}
+
+impl Preset {
+    /// Name shown in the controls menu
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Arrows => "ARROWS",
+            Preset::Wasd => "WASD",
+            Preset::Hjkl => "HJKL",
+        }
+    }
+
+    /// Preset after this one, back to the first after the last
+    pub fn next(self) -> Self {
+        let index = PRESETS
+            .iter()
+            .position(|&preset| preset == self)
+            .unwrap_or(0);
+        PRESETS[(index + 1) % PRESETS.len()]
+    }
+
+    /// Preset before this one, back to the last before the first
+    pub fn previous(self) -> Self {
+        let index = PRESETS
+            .iter()
+            .position(|&preset| preset == self)
+            .unwrap_or(0);
+        PRESETS[(index + PRESETS.len() - 1) % PRESETS.len()]
+    }
+}
+
+/// Keys bound to every action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bindings {
+    /// Key of each action, in the order of ACTIONS
+    keys: [Key; ACTION_COUNT],
+}
+
+impl Bindings {
+    /// Create bindings of given preset
+    pub fn preset(preset: Preset) -> Self {
+        let arrows = [Key::Up, Key::Down, Key::Left, Key::Right];
+        let wasd = [
+            Key::Char(b'W'),
+            Key::Char(b'S'),
+            Key::Char(b'A'),
+            Key::Char(b'D'),
+        ];
+        let hjkl = [
+            Key::Char(b'K'),
+            Key::Char(b'J'),
+            Key::Char(b'H'),
+            Key::Char(b'L'),
+        ];
+        let (first, second) = match preset {
+            Preset::Arrows => (arrows, wasd),
+            Preset::Wasd => (wasd, arrows),
+            Preset::Hjkl => (hjkl, wasd),
+        };
+        let mut keys = [Key::Up; ACTION_COUNT];
+        keys[..4].copy_from_slice(&first);
+        keys[4..8].copy_from_slice(&second);
+        keys[8..].copy_from_slice(&[Key::Char(b'P'), Key::Char(b' '), Key::Char(b'Q')]);
+        Bindings { keys }
+    }
+
+    /// Get the preset these bindings are, if they were not changed since
+    pub fn matching_preset(&self) -> Option<Preset> {
+        PRESETS
+            .iter()
+            .cloned()
+            .find(|&preset| Self::preset(preset) == *self)
+    }
+
+    /// Get the key bound to given action
+    pub fn key(&self, action: Action) -> Key {
+        let index = ACTIONS.iter().position(|&a| a == action).unwrap_or(0);
+        self.keys[index]
+    }
+
+    /// Get the action bound to given key, if any
+    pub fn action(&self, key: Key) -> Option<Action> {
+        self.keys
+            .iter()
+            .position(|&k| k == key)
+            .map(|index| ACTIONS[index])
+    }
+
+    /// Bind given key to given action. The action the key was bound to takes
+    /// the key this action had, so that every key still does a single thing.
+    /// Return false and change nothing if the key is reserved.
+    pub fn bind(&mut self, action: Action, key: Key) -> bool {
+        if key.is_reserved() {
+            return false;
+        }
+        let index = match ACTIONS.iter().position(|&a| a == action) {
+            Some(index) => index,
+            None => return false,
+        };
+        if let Some(other) = self.keys.iter().position(|&k| k == key) {
+            self.keys[other] = self.keys[index];
+        }
+        self.keys[index] = key;
+        true
+    }
+
+    /// Encode the bindings to be stored
+    pub fn encode(&self) -> [u8; ENCODED_SIZE] {
+        let mut bytes = [0; ENCODED_SIZE];
+        for (byte, key) in bytes.iter_mut().zip(self.keys.iter()) {
+            *byte = key.code();
+        }
+        bytes
+    }
+
+    /// Decode stored bindings, if they are well formed: a valid key per action, none bound twice nor reserved
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != ENCODED_SIZE {
+            return None;
+        }
+        let mut keys = [Key::Up; ACTION_COUNT];
+        for (i, &byte) in bytes.iter().enumerate() {
+            let key = Key::from_code(byte).filter(|key| !key.is_reserved())?;
+            if keys[..i].contains(&key) {
+                return None;
+            }
+            keys[i] = key;
+        }
+        Some(Bindings { keys })
+    }
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self::preset(Preset::Arrows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_bind_every_key_once() {
+        for &preset in PRESETS.iter() {
+            let bindings = Bindings::preset(preset);
+            assert_eq!(bindings.matching_preset(), Some(preset));
+            for &action in ACTIONS.iter() {
+                assert_eq!(bindings.action(bindings.key(action)), Some(action));
+            }
+        }
+        let hjkl = Bindings::preset(Preset::Hjkl);
+        assert_eq!(
+            hjkl.action(Key::from_char('h').unwrap()),
+            Some(Action::Turn(0, Direction::Left))
+        );
+        assert_eq!(hjkl.action(Key::Char(b' ')), Some(Action::Restart));
+        assert_eq!(hjkl.action(Key::Up), None);
+    }
+
+    #[test]
+    fn binding_a_taken_key_swaps_keys() {
+        let mut bindings = Bindings::default();
+        assert!(bindings.bind(Action::Pause, Key::Up));
+        assert_eq!(bindings.key(Action::Pause), Key::Up);
+        assert_eq!(
+            bindings.key(Action::Turn(0, Direction::Up)),
+            Key::Char(b'P')
+        );
+        assert_eq!(bindings.matching_preset(), None);
+
+        assert!(!bindings.bind(Action::Quit, Key::ESCAPE));
+        assert!(!bindings.bind(Action::Quit, Key::BACKSPACE));
+        assert_eq!(bindings.key(Action::Quit), Key::Char(b'Q'));
+    }
+
+    #[test]
+    fn decodes_encoded_bindings() {
+        let mut bindings = Bindings::preset(Preset::Wasd);
+        bindings.bind(Action::Quit, Key::from_char('x').unwrap());
+        let bytes = bindings.encode();
+        assert_eq!(Bindings::decode(&bytes), Some(bindings));
+
+        // Keys bound twice, reserved or unknown are refused
+        let mut twice = bytes;
+        twice[1] = twice[0];
+        assert_eq!(Bindings::decode(&twice), None);
+        let mut reserved = bytes;
+        reserved[0] = b'\n';
+        assert_eq!(Bindings::decode(&reserved), None);
+        let mut unknown = bytes;
+        unknown[0] = 0x90;
+        assert_eq!(Bindings::decode(&unknown), None);
+        assert_eq!(Bindings::decode(&bytes[1..]), None);
+    }
+
+    #[test]
+    fn keys_are_read_from_characters() {
+        assert_eq!(Key::from_char('k'), Some(Key::Char(b'K')));
+        assert_eq!(Key::from_char('\u{1b}'), Some(Key::ESCAPE));
+        assert_eq!(Key::from_char('\t'), None);
+        assert_eq!(Key::from_char('é'), None);
+    }
+}
@@ -0,0 +1,307 @@
+/* In-memory model of the play field
+//
+// The board is the only source of truth for what occupies the cells inside the
+// boundary. Game logic reads and updates the board, and the renderer projects it
+// on the screen.
+*/
+
+use crate::food::FoodKind;
+use crate::snake::Direction;
+
+// Fits inside the 80x25 VGA text buffer along with the boundary and a score row
+pub const BOARD_HEIGHT: usize = 22;
+pub const BOARD_WIDTH: usize = 78;
+
+/// Shape of a part of snake's body, named after the two sides of the cell it connects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyShape {
+    Horizontal,
+    Vertical,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl BodyShape {
+    /// Shape of the body part left behind when snake moving in `from` direction moves on in `to` direction
+    pub fn joining(from: Direction, to: Direction) -> Self {
+        match (from, to) {
+            (Direction::Left, Direction::Left) | (Direction::Right, Direction::Right) => {
+                BodyShape::Horizontal
+            }
+            (Direction::Up, Direction::Up) | (Direction::Down, Direction::Down) => {
+                BodyShape::Vertical
+            }
+            (Direction::Right, Direction::Up) | (Direction::Down, Direction::Left) => {
+                BodyShape::UpLeft
+            }
+            (Direction::Left, Direction::Up) | (Direction::Down, Direction::Right) => {
+                BodyShape::UpRight
+            }
+            (Direction::Right, Direction::Down) | (Direction::Up, Direction::Left) => {
+                BodyShape::DownLeft
+            }
+            (Direction::Left, Direction::Down) | (Direction::Up, Direction::Right) => {
+                BodyShape::DownRight
+            }
+            _ => panic!("Snake cannot reverse from {:?} to {:?}", from, to),
+        }
+    }
+}
+
+/// Content of a single cell on the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    Wall,
+    Food(FoodKind),
+    Head(Direction),
+    Body(BodyShape),
+}
+
+/// What happens when snake moves past an edge of the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edges {
+    /// Edges are walls, and running into one ends the game
+    Solid,
+    /// Snake leaving the board re-enters it from the opposite edge
+    Wrap,
+}
+
+impl Default for Edges {
+    fn default() -> Self {
+        Edges::Solid
+    }
+}
+
+/// Co-ordinates of a cell on the board
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Position {
+    /// Position of the neighbouring cell in given direction, or None if it lies outside the board
+    pub fn step(self, direction: Direction) -> Option<Position> {
+        match direction {
+            Direction::Left if self.col > 0 => Some(Position {
+                row: self.row,
+                col: self.col - 1,
+            }),
+            Direction::Right if self.col + 1 < BOARD_WIDTH => Some(Position {
+                row: self.row,
+                col: self.col + 1,
+            }),
+            Direction::Up if self.row > 0 => Some(Position {
+                row: self.row - 1,
+                col: self.col,
+            }),
+            Direction::Down if self.row + 1 < BOARD_HEIGHT => Some(Position {
+                row: self.row + 1,
+                col: self.col,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Position of the neighbouring cell in given direction, continuing from the opposite edge when it lies outside the board
+    pub fn step_wrapping(self, direction: Direction) -> Position {
+        match direction {
+            Direction::Left => Position {
+                row: self.row,
+                col: (self.col + BOARD_WIDTH - 1) % BOARD_WIDTH,
+            },
+            Direction::Right => Position {
+                row: self.row,
+                col: (self.col + 1) % BOARD_WIDTH,
+            },
+            Direction::Up => Position {
+                row: (self.row + BOARD_HEIGHT - 1) % BOARD_HEIGHT,
+                col: self.col,
+            },
+            Direction::Down => Position {
+                row: (self.row + 1) % BOARD_HEIGHT,
+                col: self.col,
+            },
+        }
+    }
+}
+
+/// Grid of cells inside the boundary
+pub struct Board {
+    cells: [[Cell; BOARD_WIDTH]; BOARD_HEIGHT],
+    edges: Edges,
+    /// Number of empty cells, kept up to date by `set`
+    empty_cells: usize,
+}
+
+impl Board {
+    /// Create a board with all cells empty
+    pub fn new(edges: Edges) -> Self {
+        Board {
+            cells: [[Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT],
+            edges,
+            empty_cells: BOARD_HEIGHT * BOARD_WIDTH,
+        }
+    }
+
+    /// Get the behaviour of board's edges
+    pub fn edges(&self) -> Edges {
+        self.edges
+    }
+
+    /// Position of the cell next to given one in given direction, or None if an edge is in the way
+    pub fn neighbour(&self, position: Position, direction: Direction) -> Option<Position> {
+        match self.edges {
+            Edges::Solid => position.step(direction),
+            Edges::Wrap => Some(position.step_wrapping(direction)),
+        }
+    }
+
+    /// Get the content of cell at given position
+    pub fn get(&self, position: Position) -> Cell {
+        self.cells[position.row][position.col]
+    }
+
+    /// Set the content of cell at given position
+    pub fn set(&mut self, position: Position, cell: Cell) {
+        let old_cell = self.cells[position.row][position.col];
+        if old_cell == Cell::Empty && cell != Cell::Empty {
+            self.empty_cells -= 1;
+        } else if old_cell != Cell::Empty && cell == Cell::Empty {
+            self.empty_cells += 1;
+        }
+        self.cells[position.row][position.col] = cell;
+    }
+
+    /// Get the number of empty cells
+    pub fn empty_cells(&self) -> usize {
+        self.empty_cells
+    }
+
+    /// Get position of the empty cell at given index, counting row by row from the top-left corner.
+    /// None if there are not that many empty cells.
+    pub fn nth_empty_cell(&self, index: usize) -> Option<Position> {
+        self.cells
+            .iter()
+            .enumerate()
+            .flat_map(|(row, cells)| {
+                cells
+                    .iter()
+                    .enumerate()
+                    .map(move |(col, cell)| (Position { row, col }, *cell))
+            })
+            .filter(|(_, cell)| *cell == Cell::Empty)
+            .map(|(position, _)| position)
+            .nth(index)
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new(Edges::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_stays_inside_board() {
+        let top_left = Position { row: 0, col: 0 };
+        assert_eq!(top_left.step(Direction::Up), None);
+        assert_eq!(top_left.step(Direction::Left), None);
+        assert_eq!(
+            top_left.step(Direction::Right),
+            Some(Position { row: 0, col: 1 })
+        );
+        let bottom_right = Position {
+            row: BOARD_HEIGHT - 1,
+            col: BOARD_WIDTH - 1,
+        };
+        assert_eq!(bottom_right.step(Direction::Down), None);
+        assert_eq!(bottom_right.step(Direction::Right), None);
+    }
+
+    #[test]
+    fn empty_cells_are_counted_and_indexed() {
+        let mut board = Board::default();
+        assert_eq!(board.empty_cells(), BOARD_HEIGHT * BOARD_WIDTH);
+        let top_left = Position { row: 0, col: 0 };
+        board.set(top_left, Cell::Wall);
+        board.set(top_left, Cell::Food(FoodKind::Regular));
+        board.set(Position { row: 0, col: 2 }, Cell::Wall);
+        assert_eq!(board.empty_cells(), BOARD_HEIGHT * BOARD_WIDTH - 2);
+        assert_eq!(board.nth_empty_cell(0), Some(Position { row: 0, col: 1 }));
+        assert_eq!(board.nth_empty_cell(1), Some(Position { row: 0, col: 3 }));
+        assert_eq!(
+            board.nth_empty_cell(BOARD_WIDTH - 2),
+            Some(Position { row: 1, col: 0 })
+        );
+        assert_eq!(board.nth_empty_cell(board.empty_cells()), None);
+        board.set(top_left, Cell::Empty);
+        assert_eq!(board.empty_cells(), BOARD_HEIGHT * BOARD_WIDTH - 1);
+        assert_eq!(board.nth_empty_cell(0), Some(top_left));
+    }
+
+    #[test]
+    fn wrapping_edges_lead_to_opposite_side() {
+        let board = Board::new(Edges::Wrap);
+        let top_left = Position { row: 0, col: 0 };
+        assert_eq!(
+            board.neighbour(top_left, Direction::Up),
+            Some(Position {
+                row: BOARD_HEIGHT - 1,
+                col: 0
+            })
+        );
+        assert_eq!(
+            board.neighbour(top_left, Direction::Left),
+            Some(Position {
+                row: 0,
+                col: BOARD_WIDTH - 1
+            })
+        );
+        let bottom_right = Position {
+            row: BOARD_HEIGHT - 1,
+            col: BOARD_WIDTH - 1,
+        };
+        assert_eq!(
+            board.neighbour(bottom_right, Direction::Down),
+            Some(Position {
+                row: 0,
+                col: BOARD_WIDTH - 1
+            })
+        );
+        assert_eq!(
+            board.neighbour(bottom_right, Direction::Right),
+            Some(Position {
+                row: BOARD_HEIGHT - 1,
+                col: 0
+            })
+        );
+        assert_eq!(
+            Board::new(Edges::Solid).neighbour(top_left, Direction::Up),
+            None
+        );
+    }
+
+    #[test]
+    fn joining_shapes_connect_entry_and_exit_sides() {
+        assert_eq!(
+            BodyShape::joining(Direction::Right, Direction::Up),
+            BodyShape::UpLeft
+        );
+        assert_eq!(
+            BodyShape::joining(Direction::Up, Direction::Right),
+            BodyShape::DownRight
+        );
+        assert_eq!(
+            BodyShape::joining(Direction::Down, Direction::Down),
+            BodyShape::Vertical
+        );
+    }
+}
@@ -0,0 +1,44 @@
+/* Kinds of food the snake can eat
+//
+// Regular food is always on the board, and eating it is what moves the game
+// on to the next level. Now and then eating it also brings out a special item
+// somewhere on the board, which disappears again after a limited number of
+// steps unless the snake gets to it first.
+*/
+
+use crate::board::Position;
+
+/// One in this many regular foods eaten brings out a special item, if none is on the board
+pub const SPECIAL_FOOD_CHANCE: u64 = 4;
+/// Steps of the snake a special item stays on the board
+pub const SPECIAL_FOOD_STEPS: u32 = 60;
+/// Cells slim food takes off the snake, never making it shorter than it started
+pub const SLIM_LENGTH: usize = 3;
+/// Speed levels speed food adds for a while
+pub const SPEED_BOOST_LEVELS: u8 = 3;
+/// Steps of the snake speed food lasts for
+pub const SPEED_BOOST_STEPS: u32 = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoodKind {
+    /// Grows the snake by one cell
+    Regular,
+    /// Grows the snake like regular food, and is worth more
+    Bonus,
+    /// Makes the snake shorter
+    Slim,
+    /// Makes the snake faster for a while
+    Speed,
+}
+
+/// Kinds of special items, which are equally likely to show up
+pub const SPECIAL_KINDS: [FoodKind; 3] = [FoodKind::Bonus, FoodKind::Slim, FoodKind::Speed];
+
+/// Special item on the board, counting down until it disappears
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpecialFood {
+    pub kind: FoodKind,
+    pub position: Position,
+    /// Steps left until the item disappears
+    pub remaining_steps: u32,
+}
@@ -0,0 +1,1559 @@
+/* Headless game simulation
+//
+// A `Game` owns the board, the snakes and their scores, and advances them one
+// step at a time. It never touches the screen, so it can be driven by the
+// kernel's timer interrupt or by a test on the host alike.
+//
+// Up to two players can play on the same board, each with a snake of their
+// own. All snakes move at once: a snake running into anything but empty cells
+// or food crashes, and so do two heads moving into the same cell. The second
+// snake can be steered by the computer instead, which picks its turns right
+// before each step as if a key was pressed.
+//
+// Turns queued by players are recorded in a replay of the game, and a replay
+// handed back to a game plays it again, queueing its turns in place of the
+// players' before the same steps.
+*/
+
+use crate::autopilot::{self, Difficulty};
+use crate::board::{Board, Cell, Edges, Position};
+use crate::food::{self, FoodKind, SpecialFood};
+use crate::high_scores::{Entry, HighScores, Initials, INITIALS_LENGTH};
+use crate::level::{self, Level};
+use crate::prng::{DefaultRng, Rng};
+use crate::replay::{Cursor, Replay, Turn};
+use crate::score::Score;
+use crate::snake::{Direction, Move, Snake};
+use crate::speed::{self, Speed};
+
+/// Most players a game can be played by, each steering a snake of their own
+pub const MAX_PLAYERS: usize = 2;
+/// Player whose snake the computer steers when playing against it
+pub const COMPUTER_PLAYER: usize = 1;
+
+/// Settings for starting a new game
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    /// Levels to play in order, each with its own walls and starting positions
+    pub levels: &'static [Level],
+    /// Index of the level in `levels` the game starts with
+    pub start_level: usize,
+    /// Food to eat on a level before moving on to the next one
+    pub foods_per_level: u16,
+    /// Number of cells occupied by the snake when a level starts
+    pub start_length: usize,
+    /// Speed level when the game starts
+    pub start_speed: u8,
+    /// Whether the snake can pass through edges of the board
+    pub edges: Edges,
+    /// Whether levels are played with their walls, or on an open board
+    pub walls: bool,
+    /// Whether special items show up besides regular food
+    pub special_foods: bool,
+    /// Number of players, each steering a snake of their own
+    pub players: usize,
+    /// Difficulty of the computer steering the second snake, or None if a person steers it
+    pub opponent: Option<Difficulty>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            levels: level::LEVELS,
+            start_level: 0,
+            foods_per_level: level::FOODS_PER_LEVEL,
+            start_length: 3,
+            start_speed: speed::DEFAULT_LEVEL,
+            edges: Edges::Solid,
+            walls: true,
+            special_foods: true,
+            players: 1,
+            opponent: None,
+        }
+    }
+}
+
+impl Config {
+    /// Index of the level games start at, limited to the levels available
+    pub fn first_level(&self) -> usize {
+        self.start_level.min(self.levels.len() - 1)
+    }
+
+    /// Number of players in games, limited to the range supported
+    pub fn player_count(&self) -> usize {
+        self.players.max(1).min(MAX_PLAYERS)
+    }
+}
+
+/// Stage of the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    /// Snake is moving around the board
+    Playing,
+    /// Game is frozen until resumed
+    Paused,
+    /// A snake collided, and the game is waiting to be restarted
+    GameOver,
+    /// Snakes filled the board, and the game is waiting to be restarted
+    Won,
+}
+
+/// Things that happened during a single step of the game
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Events {
+    /// Kind of food each player's snake ate
+    pub ate_food: [Option<FoodKind>; MAX_PLAYERS],
+    /// Special item disappeared before a snake got to it
+    pub special_food_expired: bool,
+    /// Whether each player's snake ran into the boundary, a wall, a body or another head
+    pub collided: [bool; MAX_PLAYERS],
+    /// Snake ate enough food to move on to the next level
+    pub level_up: bool,
+    /// Snake filled the board and won the game
+    pub won: bool,
+}
+
+pub struct Game<'a> {
+    config: Config,
+    state: State,
+    board: Board,
+    /// Number of players in the current game
+    players: usize,
+    /// Difficulty of the computer steering the second snake in the current game, if it does
+    opponent: Option<Difficulty>,
+    /// Snakes of the players, of which the ones past `players` stay off the board
+    snakes: [Snake<'a>; MAX_PLAYERS],
+    scores: [Score; MAX_PLAYERS],
+    speed: Speed,
+    prng: DefaultRng,
+    /// Seed food placement of the current game started from
+    seed: u64,
+    /// Index of the level being played
+    level: usize,
+    /// Food eaten since the current level started
+    level_foods: u16,
+    /// Special item on the board, if any
+    special_food: Option<SpecialFood>,
+    /// Best scores of games played so far
+    high_scores: HighScores,
+    /// Initials being typed in after a game that made it into high scores
+    initials: Option<Initials>,
+    /// Player who won the last game played by more than one player, None for a draw
+    winner: Option<usize>,
+    /// Steps taken since the current game started
+    steps: u32,
+    /// Recording of the current game
+    recording: Replay,
+    /// Replay being played back in the current game, if it is one
+    playback: Option<Playback>,
+}
+
+/// Replay played back by a game
+struct Playback {
+    replay: Replay,
+    /// Next turn to queue
+    cursor: Cursor,
+    /// Configuration games started after the replay use
+    config: Config,
+}
+
+impl<'a> Game<'a> {
+    /// Start a new game. `body_buffer` holds positions of snakes' bodies, and should have room for
+    /// `MAX_SNAKE_SIZE` of them for each of `MAX_PLAYERS`.
+    pub fn new(seed: u64, config: Config, body_buffer: &'a mut [Position]) -> Self {
+        let (first, second) = body_buffer.split_at_mut(body_buffer.len() / MAX_PLAYERS);
+        let mut game = Game {
+            config,
+            state: State::Playing,
+            board: Board::new(config.edges),
+            players: config.player_count(),
+            opponent: None,
+            snakes: [Snake::with_buffer(first), Snake::with_buffer(second)],
+            scores: [Score::new(0); MAX_PLAYERS],
+            speed: Speed::new(config.start_speed),
+            prng: DefaultRng::new(seed),
+            seed,
+            level: 0,
+            level_foods: 0,
+            special_food: None,
+            high_scores: HighScores::new(),
+            initials: None,
+            winner: None,
+            steps: 0,
+            recording: Replay::new(seed, &config),
+            playback: None,
+        };
+        game.opponent = game.current_opponent();
+        game.load_level(config.first_level());
+        game
+    }
+
+    /// Start a new game with the same configuration, placing food from given seed.
+    /// A game started from the same seed and played with the same moves ends up the same.
+    /// Initials not submitted yet are dropped, leaving the last score out of high scores.
+    pub fn restart(&mut self, seed: u64) {
+        if let Some(playback) = self.playback.take() {
+            self.config = playback.config;
+        }
+        self.reset(seed);
+    }
+
+    /// Start playing given replay back, with the seed and options its game started with.
+    /// Options chosen for games started afterwards stay as they are.
+    pub fn play_replay(&mut self, replay: Replay) {
+        let config = *self.config();
+        self.config = replay.config(config);
+        self.reset(replay.seed());
+        self.playback = Some(Playback {
+            cursor: replay.start(),
+            replay,
+            config,
+        });
+    }
+
+    /// Start a new game with current configuration from given seed
+    fn reset(&mut self, seed: u64) {
+        self.prng = DefaultRng::new(seed);
+        self.seed = seed;
+        self.board = Board::new(self.config.edges);
+        self.players = self.config.player_count();
+        self.opponent = self.current_opponent();
+        self.load_level(self.config.first_level());
+        self.scores = [Score::new(0); MAX_PLAYERS];
+        self.speed = Speed::new(self.config.start_speed);
+        self.state = State::Playing;
+        self.winner = None;
+        self.initials = None;
+        self.steps = 0;
+        self.recording = Replay::new(seed, &self.config);
+    }
+
+    /// Difficulty of the computer opponent configured, if the game is played by enough players to have one
+    fn current_opponent(&self) -> Option<Difficulty> {
+        if self.players > 1 {
+            self.config.opponent
+        } else {
+            None
+        }
+    }
+
+    /// Clear the board and set up walls, snakes and food of the level at given index
+    fn load_level(&mut self, level: usize) {
+        self.board = Board::new(self.board.edges());
+        self.level = level;
+        self.level_foods = 0;
+        self.special_food = None;
+        let level = self.config.levels[level];
+        if self.config.walls {
+            level.place_walls(&mut self.board);
+        }
+        for (player, snake) in self.snakes.iter_mut().enumerate() {
+            if player < self.players {
+                let (head, direction) = level.start(player);
+                snake.respawn(head, direction, self.config.start_length, &mut self.board);
+            } else {
+                snake.clear();
+            }
+        }
+        // Second player's snake may lie where the level puts the first food
+        let food_position = if self.board.get(level.food_start_position) == Cell::Empty {
+            Some(level.food_start_position)
+        } else {
+            self.random_empty_cell()
+        };
+        if let Some(position) = food_position {
+            self.board.set(position, Cell::Food(FoodKind::Regular));
+        }
+    }
+
+    /// Configuration games started from now on use
+    fn next_config(&mut self) -> &mut Config {
+        match self.playback.as_mut() {
+            Some(playback) => &mut playback.config,
+            None => &mut self.config,
+        }
+    }
+
+    /// Choose speed level for games started from now on
+    pub fn set_start_speed(&mut self, level: u8) {
+        self.next_config().start_speed = level;
+    }
+
+    /// Choose level for games started from now on. Levels past the last one start the last level.
+    pub fn set_start_level(&mut self, level: usize) {
+        self.next_config().start_level = level;
+    }
+
+    /// Choose behaviour of board's edges for games started from now on
+    pub fn set_edges(&mut self, edges: Edges) {
+        self.next_config().edges = edges;
+    }
+
+    /// Choose number of players for games started from now on
+    pub fn set_players(&mut self, players: usize) {
+        self.next_config().players = players;
+    }
+
+    /// Choose whether the computer steers the second snake in games started from now on, and how well
+    pub fn set_opponent(&mut self, opponent: Option<Difficulty>) {
+        self.next_config().opponent = opponent;
+    }
+
+    /// Count one tick of the timer driving the game, and step the game whenever current speed says so.
+    /// Return events of the step if one was taken.
+    pub fn tick(&mut self) -> Option<Events> {
+        if self.state == State::Playing && self.speed.tick() {
+            Some(self.step(None))
+        } else {
+            None
+        }
+    }
+
+    /// Pause the game while playing, or resume a paused game
+    pub fn toggle_pause(&mut self) {
+        self.state = match self.state {
+            State::Playing => State::Paused,
+            State::Paused => State::Playing,
+            state => state,
+        };
+    }
+
+    /// Queue a direction for the first player's snake to turn to on an upcoming step. Ignored unless playing.
+    pub fn set_turn_direction(&mut self, turn_direction: Direction) {
+        self.set_player_turn_direction(0, turn_direction);
+    }
+
+    /// Queue a direction for given player's snake to turn to on an upcoming step. Ignored unless playing,
+    /// if the player is not in the game or steered by the computer, or while a replay is played back.
+    pub fn set_player_turn_direction(&mut self, player: usize, turn_direction: Direction) {
+        if self.state == State::Playing
+            && player < self.players
+            && !self.is_computer(player)
+            && self.playback.is_none()
+        {
+            self.queue_turn(Turn {
+                step: self.steps,
+                player,
+                direction: turn_direction,
+            });
+        }
+    }
+
+    /// Queue a turn of a player's snake, and record it unless the snake ignored it
+    fn queue_turn(&mut self, turn: Turn) {
+        if self.snakes[turn.player].set_turn_direction(turn.direction) {
+            self.recording.record(turn);
+        }
+    }
+
+    /// Queue turns of the replay played back which were queued before the coming step
+    fn queue_replayed_turns(&mut self) {
+        loop {
+            let turn = match self.playback.as_mut() {
+                Some(playback) => {
+                    let mut cursor = playback.cursor;
+                    match playback.replay.next_turn(&mut cursor) {
+                        Some(turn) if turn.step == self.steps && turn.player < self.players => {
+                            playback.cursor = cursor;
+                            turn
+                        }
+                        _ => return,
+                    }
+                }
+                None => return,
+            };
+            self.queue_turn(turn);
+        }
+    }
+
+    /// Advance the game by one step, queueing `input` as a turn of the first player's snake first if given.
+    /// Nothing happens while paused or once the game is over.
+    pub fn step(&mut self, input: Option<Direction>) -> Events {
+        let mut events = Events::default();
+        if self.state != State::Playing {
+            return events;
+        }
+        if let Some(turn_direction) = input {
+            self.set_player_turn_direction(0, turn_direction);
+        }
+        self.queue_replayed_turns();
+        if let Some(difficulty) = self.opponent {
+            let snake = &mut self.snakes[COMPUTER_PLAYER];
+            if let Some(turn_direction) =
+                autopilot::choose_direction(&self.board, snake, difficulty)
+            {
+                snake.set_turn_direction(turn_direction);
+            }
+        }
+        events.collided = self.collisions();
+        for player in 0..self.players {
+            if events.collided[player] {
+                // Snake is left where it was
+                continue;
+            }
+            match self.snakes[player].tick(&mut self.board) {
+                Move::Moved => (),
+                Move::Collided => events.collided[player] = true,
+                Move::Ate(kind) => {
+                    // We ate food. Increment score and Grow!
+                    events.ate_food[player] = Some(kind);
+                    self.scores[player].eat(kind);
+                    self.speed.update(self.total_score());
+                }
+            }
+        }
+        if events.collided.iter().any(|collided| *collided) {
+            self.finish(State::GameOver, events.collided);
+        } else {
+            // Food takes effect once all snakes moved, as a new level puts every snake back at its start.
+            // Food eaten by the other snake is forgotten once a new level started or the game was won.
+            for player in 0..self.players {
+                if events.level_up || events.won {
+                    break;
+                }
+                match events.ate_food[player] {
+                    Some(FoodKind::Regular) => self.ate_regular_food(&mut events),
+                    Some(kind) => self.ate_special_food(player, kind),
+                    None => (),
+                }
+            }
+        }
+        self.speed.step();
+        self.steps += 1;
+        if self.state == State::Playing && !events.level_up {
+            events.special_food_expired = self.count_down_special_food();
+        }
+        events
+    }
+
+    /// Check which snakes run into something on the next step: the boundary, a wall, a snake,
+    /// or another head moving into the same cell
+    fn collisions(&self) -> [bool; MAX_PLAYERS] {
+        let mut targets = [None; MAX_PLAYERS];
+        for (target, snake) in targets
+            .iter_mut()
+            .zip(self.snakes.iter())
+            .take(self.players)
+        {
+            *target = snake.next_head(&self.board);
+        }
+        let mut collided = [false; MAX_PLAYERS];
+        for (player, target) in targets.iter().enumerate().take(self.players) {
+            collided[player] = match *target {
+                None => true,
+                Some(position) => match self.board.get(position) {
+                    Cell::Wall | Cell::Head(_) | Cell::Body(_) => true,
+                    Cell::Empty | Cell::Food(_) => targets
+                        .iter()
+                        .enumerate()
+                        .any(|(other, other_target)| other != player && *other_target == *target),
+                },
+            };
+        }
+        collided
+    }
+
+    /// Move on to the next level once enough regular food is eaten, or put new food on the board
+    fn ate_regular_food(&mut self, events: &mut Events) {
+        self.level_foods += 1;
+        if self.level_foods >= self.config.foods_per_level
+            && self.level + 1 < self.config.levels.len()
+        {
+            events.level_up = true;
+            self.load_level(self.level + 1);
+        } else if let Some(position) = self.random_empty_cell() {
+            self.board.set(position, Cell::Food(FoodKind::Regular));
+            self.maybe_place_special_food();
+        } else {
+            // Snake fills every cell not taken by walls
+            events.won = true;
+            self.finish(State::Won, [false; MAX_PLAYERS]);
+        }
+    }
+
+    /// Apply the effect of a special item eaten by given player's snake
+    fn ate_special_food(&mut self, player: usize, kind: FoodKind) {
+        self.special_food = None;
+        match kind {
+            // Eating grew the snake by one cell, which is taken off too
+            FoodKind::Slim => self.snakes[player].shrink(
+                food::SLIM_LENGTH + 1,
+                self.config.start_length,
+                &mut self.board,
+            ),
+            FoodKind::Speed => self
+                .speed
+                .boost(food::SPEED_BOOST_LEVELS, food::SPEED_BOOST_STEPS),
+            FoodKind::Regular | FoodKind::Bonus => (),
+        }
+    }
+
+    /// Now and then put a special item on the board, unless one is there already
+    fn maybe_place_special_food(&mut self) {
+        if !self.config.special_foods
+            || self.special_food.is_some()
+            || self.prng.gen_range(food::SPECIAL_FOOD_CHANCE) != 0
+        {
+            return;
+        }
+        let kind =
+            food::SPECIAL_KINDS[self.prng.gen_range(food::SPECIAL_KINDS.len() as u64) as usize];
+        if let Some(position) = self.random_empty_cell() {
+            self.board.set(position, Cell::Food(kind));
+            self.special_food = Some(SpecialFood {
+                kind,
+                position,
+                remaining_steps: food::SPECIAL_FOOD_STEPS,
+            });
+        }
+    }
+
+    /// Count a step towards the special item disappearing. Return true if it disappeared.
+    fn count_down_special_food(&mut self) -> bool {
+        match self.special_food.as_mut() {
+            Some(special_food) if special_food.remaining_steps > 1 => {
+                special_food.remaining_steps -= 1;
+                false
+            }
+            Some(special_food) => {
+                self.board.set(special_food.position, Cell::Empty);
+                self.special_food = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// End the game, given which snakes collided. With more than one player someone may win,
+    /// while a single player is asked for initials if the score made it into high scores.
+    /// Scores of replays played back are already in high scores if they made it.
+    fn finish(&mut self, state: State, collided: [bool; MAX_PLAYERS]) {
+        self.state = state;
+        if self.players > 1 {
+            self.winner = self.decide_winner(collided);
+        } else if self.playback.is_none() && self.high_scores.qualifies(self.scores[0].get_score())
+        {
+            self.initials = Some(Initials::default());
+        }
+    }
+
+    /// Player who did not crash, or else the one with the higher score. None for a draw.
+    fn decide_winner(&self, collided: [bool; MAX_PLAYERS]) -> Option<usize> {
+        let mut candidates = [false; MAX_PLAYERS];
+        for (candidate, collided) in candidates.iter_mut().zip(collided.iter()) {
+            *candidate = !collided;
+        }
+        // Scores decide between players who all crashed, or all filled the board together
+        if !candidates
+            .iter()
+            .take(self.players)
+            .any(|candidate| *candidate)
+        {
+            candidates = [true; MAX_PLAYERS];
+        }
+        let is_candidate = |player: &usize| candidates[*player];
+        let best = (0..self.players)
+            .filter(is_candidate)
+            .map(|player| self.scores[player].get_score())
+            .max()?;
+        let mut leaders = (0..self.players)
+            .filter(is_candidate)
+            .filter(|player| self.scores[*player].get_score() == best);
+        match (leaders.next(), leaders.next()) {
+            (Some(player), None) => Some(player),
+            _ => None,
+        }
+    }
+
+    /// Replace the high scores, for instance with ones read from storage
+    pub fn set_high_scores(&mut self, high_scores: HighScores) {
+        self.high_scores = high_scores;
+    }
+
+    /// Type a letter of player's initials. Ignored unless initials are being entered.
+    pub fn enter_initial(&mut self, letter: char) {
+        if let Some(initials) = self.initials.as_mut() {
+            initials.push(letter);
+        }
+    }
+
+    /// Erase the last letter of player's initials typed
+    pub fn delete_initial(&mut self) {
+        if let Some(initials) = self.initials.as_mut() {
+            initials.pop();
+        }
+    }
+
+    /// Give up on entering initials, leaving high scores as they were
+    pub fn skip_initials(&mut self) {
+        self.initials = None;
+    }
+
+    /// Add the score to high scores under initials typed so far, if all of them were typed.
+    /// Return true if high scores changed.
+    pub fn submit_initials(&mut self) -> bool {
+        match self.initials {
+            Some(initials) if initials.is_complete() => {
+                let mut entry = Entry {
+                    initials: [0; INITIALS_LENGTH],
+                    score: self.scores[0].get_score(),
+                };
+                entry.initials.copy_from_slice(initials.as_str().as_bytes());
+                self.high_scores.insert(entry);
+                self.initials = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Pick an empty cell of the board, every one of them equally likely. None if there is no empty cell left.
+    fn random_empty_cell(&mut self) -> Option<Position> {
+        let empty_cells = self.board.empty_cells();
+        if empty_cells == 0 {
+            return None;
+        }
+        let index = self.prng.gen_range(empty_cells as u64) as usize;
+        self.board.nth_empty_cell(index)
+    }
+
+    /// Get the seed food placement of the current game started from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Get the configuration new games are started with
+    pub fn config(&self) -> &Config {
+        match self.playback.as_ref() {
+            Some(playback) => &playback.config,
+            None => &self.config,
+        }
+    }
+
+    /// Get the level being played
+    pub fn level(&self) -> &Level {
+        &self.config.levels[self.level]
+    }
+
+    /// Get the number of the level being played, counting from 1
+    pub fn level_number(&self) -> usize {
+        self.level + 1
+    }
+
+    /// Get the current stage of the game
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// Check if the game has ended, either lost or won
+    pub fn is_over(&self) -> bool {
+        self.state == State::GameOver || self.state == State::Won
+    }
+
+    /// Get the recording of the current game so far
+    pub fn replay(&self) -> &Replay {
+        &self.recording
+    }
+
+    /// Check if the current game plays a replay back
+    pub fn is_replaying(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    /// Get the board with current state of every cell
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Get the number of players in the current game
+    pub fn players(&self) -> usize {
+        self.players
+    }
+
+    /// Get the difficulty of the computer steering the second snake in the current game, if it does
+    pub fn opponent(&self) -> Option<Difficulty> {
+        self.opponent
+    }
+
+    /// Check if given player's snake is steered by the computer in the current game
+    pub fn is_computer(&self, player: usize) -> bool {
+        player == COMPUTER_PLAYER && self.opponent.is_some()
+    }
+
+    /// Get the first player's snake
+    pub fn snake(&self) -> &Snake<'a> {
+        &self.snakes[0]
+    }
+
+    /// Get given player's snake, which is off the board if the player is not in the game
+    pub fn snake_of(&self, player: usize) -> &Snake<'a> {
+        &self.snakes[player]
+    }
+
+    /// Get the first player's current score
+    pub fn score(&self) -> &Score {
+        &self.scores[0]
+    }
+
+    /// Get given player's current score
+    pub fn score_of(&self, player: usize) -> &Score {
+        &self.scores[player]
+    }
+
+    /// Sum of the scores of all players, which sets the speed of the game
+    fn total_score(&self) -> u16 {
+        self.scores
+            .iter()
+            .fold(0, |total, score| total.saturating_add(score.get_score()))
+    }
+
+    /// Get the player who won the game once it is over with more than one player, None for a draw
+    pub fn winner(&self) -> Option<usize> {
+        self.winner
+    }
+
+    /// Get the current speed
+    pub fn speed(&self) -> &Speed {
+        &self.speed
+    }
+
+    /// Get the special item on the board, if any
+    pub fn special_food(&self) -> Option<&SpecialFood> {
+        self.special_food.as_ref()
+    }
+
+    /// Get the best scores of games played so far
+    pub fn high_scores(&self) -> &HighScores {
+        &self.high_scores
+    }
+
+    /// Get initials typed so far, if the game is waiting for them
+    pub fn initials(&self) -> Option<&Initials> {
+        self.initials.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{BOARD_HEIGHT, BOARD_WIDTH};
+    use crate::level::Wall;
+    use crate::snake::MAX_SNAKE_SIZE;
+
+    const CENTRE: Position = Position {
+        row: BOARD_HEIGHT / 2,
+        col: BOARD_WIDTH / 2,
+    };
+
+    /// Open field with food right in front of the snake, followed by a level with a wall
+    const FOOD_AHEAD: &[Level] = &[
+        Level {
+            name: "FOOD AHEAD",
+            walls: &[],
+            start_position: CENTRE,
+            start_direction: Direction::Left,
+            food_start_position: Position {
+                row: CENTRE.row,
+                col: CENTRE.col - 1,
+            },
+        },
+        Level {
+            name: "WALLED",
+            walls: &[Wall {
+                top_left: Position { row: 8, col: 8 },
+                height: 4,
+                width: 4,
+            }],
+            start_position: Position { row: 3, col: 3 },
+            start_direction: Direction::Right,
+            food_start_position: Position { row: 15, col: 60 },
+        },
+    ];
+
+    /// Open field with the snake heading along the top edge
+    const TOP_EDGE: &[Level] = &[Level {
+        name: "TOP EDGE",
+        walls: &[],
+        start_position: Position { row: 0, col: 5 },
+        start_direction: Direction::Left,
+        food_start_position: Position { row: 1, col: 18 },
+    }];
+
+    /// Open field with the snake about to leave through the left edge
+    const LEFT_EDGE: &[Level] = &[Level {
+        name: "LEFT EDGE",
+        walls: &[],
+        start_position: Position { row: 5, col: 0 },
+        start_direction: Direction::Left,
+        food_start_position: Position { row: 1, col: 18 },
+    }];
+
+    /// Top row as the only open cells, which the snake can circle around forever through wrapping edges
+    const SINGLE_ROW: &[Level] = &[Level {
+        name: "SINGLE ROW",
+        walls: &[Wall {
+            top_left: Position { row: 1, col: 0 },
+            height: BOARD_HEIGHT - 1,
+            width: BOARD_WIDTH,
+        }],
+        start_position: Position { row: 0, col: 5 },
+        start_direction: Direction::Left,
+        food_start_position: Position { row: 0, col: 1 },
+    }];
+
+    fn body_buffer() -> [Position; MAX_SNAKE_SIZE * MAX_PLAYERS] {
+        [Position::default(); MAX_SNAKE_SIZE * MAX_PLAYERS]
+    }
+
+    /// Put a special item on the board as if it showed up by chance
+    fn put_special_food(game: &mut Game, kind: FoodKind, position: Position) {
+        game.board.set(position, Cell::Food(kind));
+        game.special_food = Some(SpecialFood {
+            kind,
+            position,
+            remaining_steps: food::SPECIAL_FOOD_STEPS,
+        });
+    }
+
+    fn food_position(board: &Board) -> Position {
+        for row in 0..BOARD_HEIGHT {
+            for col in 0..BOARD_WIDTH {
+                if board.get(Position { row, col }) == Cell::Food(FoodKind::Regular) {
+                    return Position { row, col };
+                }
+            }
+        }
+        panic!("no food on the board");
+    }
+
+    fn food_count(board: &Board) -> usize {
+        let mut count = 0;
+        for row in 0..BOARD_HEIGHT {
+            for col in 0..BOARD_WIDTH {
+                if board.get(Position { row, col }) == Cell::Food(FoodKind::Regular) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn snake_moves_ahead_every_step() {
+        let mut buffer = body_buffer();
+        let mut game = Game::new(1, Config::default(), &mut buffer);
+        let start = game.snake().head();
+        let events = game.step(None);
+        assert_eq!(events, Events::default());
+        let head = game.snake().head();
+        assert_eq!(head, start.step(Direction::Left).unwrap());
+        assert_eq!(game.board().get(head), Cell::Head(Direction::Left));
+        // Tail moved along with the head
+        assert_eq!(
+            game.board().get(Position {
+                row: start.row,
+                col: start.col + 2
+            }),
+            Cell::Empty
+        );
+        assert_eq!(game.snake().length(), 3);
+    }
+
+    #[test]
+    fn snake_turns_but_never_reverses() {
+        let mut buffer = body_buffer();
+        let mut game = Game::new(1, Config::default(), &mut buffer);
+        game.step(Some(Direction::Right));
+        assert_eq!(game.snake().direction(), Direction::Left);
+        let before = game.snake().head();
+        game.step(Some(Direction::Up));
+        assert_eq!(game.snake().direction(), Direction::Up);
+        assert_eq!(game.snake().head(), before.step(Direction::Up).unwrap());
+    }
+
+    #[test]
+    fn snake_grows_after_eating_food() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            levels: FOOD_AHEAD,
+            ..Config::default()
+        };
+        let mut game = Game::new(7, config, &mut buffer);
+        let events = game.step(None);
+        assert_eq!(events.ate_food[0], Some(FoodKind::Regular));
+        assert_eq!(game.snake().length(), 4);
+        assert_eq!(game.score().get_score(), 1);
+        // New food was placed somewhere else
+        assert_eq!(food_count(game.board()), 1);
+    }
+
+    #[test]
+    fn snake_collides_with_boundary() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            levels: TOP_EDGE,
+            ..Config::default()
+        };
+        let mut game = Game::new(1, config, &mut buffer);
+        let events = game.step(Some(Direction::Up));
+        assert!(events.collided[0]);
+        assert_eq!(game.state(), State::GameOver);
+        assert_eq!(game.snake().head(), Position { row: 0, col: 5 });
+        // Game stays over until restarted
+        assert_eq!(game.step(Some(Direction::Left)), Events::default());
+        assert_eq!(game.snake().head(), Position { row: 0, col: 5 });
+    }
+
+    #[test]
+    fn snake_wraps_around_edges() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            levels: LEFT_EDGE,
+            edges: Edges::Wrap,
+            ..Config::default()
+        };
+        let mut game = Game::new(1, config, &mut buffer);
+        let events = game.step(None);
+        assert!(!events.collided[0]);
+        assert_eq!(game.state(), State::Playing);
+        assert_eq!(
+            game.snake().head(),
+            Position {
+                row: 5,
+                col: BOARD_WIDTH - 1
+            }
+        );
+        assert_eq!(
+            game.board().get(game.snake().head()),
+            Cell::Head(Direction::Left)
+        );
+        for _ in 0..6 {
+            game.step(Some(Direction::Up));
+        }
+        assert_eq!(
+            game.snake().head(),
+            Position {
+                row: BOARD_HEIGHT - 1,
+                col: BOARD_WIDTH - 1
+            }
+        );
+    }
+
+    #[test]
+    fn edges_apply_after_restart() {
+        let mut buffer = body_buffer();
+        let mut game = Game::new(1, Config::default(), &mut buffer);
+        game.set_edges(Edges::Wrap);
+        assert_eq!(game.board().edges(), Edges::Solid);
+        game.restart(1);
+        assert_eq!(game.board().edges(), Edges::Wrap);
+    }
+
+    #[test]
+    fn snake_collides_with_itself() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            start_length: 5,
+            ..Config::default()
+        };
+        let mut game = Game::new(1, config, &mut buffer);
+        assert!(!game.step(Some(Direction::Up)).collided[0]);
+        assert!(!game.step(Some(Direction::Right)).collided[0]);
+        assert!(game.step(Some(Direction::Down)).collided[0]);
+    }
+
+    #[test]
+    fn timer_ticks_step_game_at_configured_speed() {
+        let mut buffer = body_buffer();
+        let mut game = Game::new(1, Config::default(), &mut buffer);
+        let start = game.snake().head();
+        let ticks_per_step = game.speed().ticks_per_step();
+        for _ in 1..ticks_per_step {
+            assert_eq!(game.tick(), None);
+        }
+        assert_eq!(game.tick(), Some(Events::default()));
+        assert_eq!(game.snake().head(), start.step(Direction::Left).unwrap());
+    }
+
+    #[test]
+    fn start_speed_applies_after_restart() {
+        let mut buffer = body_buffer();
+        let mut game = Game::new(1, Config::default(), &mut buffer);
+        assert_eq!(game.speed().level(), speed::DEFAULT_LEVEL);
+        game.set_start_speed(speed::MAX_LEVEL);
+        assert_eq!(game.speed().level(), speed::DEFAULT_LEVEL);
+        game.restart(1);
+        assert_eq!(game.speed().level(), speed::MAX_LEVEL);
+    }
+
+    #[test]
+    fn paused_game_does_not_move() {
+        let mut buffer = body_buffer();
+        let mut game = Game::new(1, Config::default(), &mut buffer);
+        let start = game.snake().head();
+        game.toggle_pause();
+        assert_eq!(game.state(), State::Paused);
+        game.set_turn_direction(Direction::Up);
+        game.step(Some(Direction::Down));
+        assert_eq!(game.snake().head(), start);
+
+        game.toggle_pause();
+        assert_eq!(game.state(), State::Playing);
+        game.step(None);
+        // Turns requested while paused are forgotten
+        assert_eq!(game.snake().head(), start.step(Direction::Left).unwrap());
+    }
+
+    #[test]
+    fn restart_resets_board_snake_and_score() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            levels: FOOD_AHEAD,
+            foods_per_level: 100,
+            ..Config::default()
+        };
+        let mut game = Game::new(3, config, &mut buffer);
+        while game.state() == State::Playing {
+            game.step(None);
+        }
+        assert_eq!(game.state(), State::GameOver);
+        assert!(game.is_over());
+        assert!(game.score().get_score() > 0);
+        // Game over cannot be paused
+        game.toggle_pause();
+        assert_eq!(game.state(), State::GameOver);
+
+        game.restart(3);
+        assert_eq!(game.state(), State::Playing);
+        assert_eq!(game.score().get_score(), 0);
+        assert_eq!(game.snake().length(), 3);
+        assert_eq!(game.snake().head(), CENTRE);
+        assert_eq!(food_count(game.board()), 1);
+        assert_eq!(
+            game.board().get(FOOD_AHEAD[0].food_start_position),
+            Cell::Food(FoodKind::Regular)
+        );
+    }
+
+    #[test]
+    fn same_seed_places_food_identically() {
+        let mut first_buffer = body_buffer();
+        let mut second_buffer = body_buffer();
+        let config = Config {
+            levels: FOOD_AHEAD,
+            ..Config::default()
+        };
+        let mut first = Game::new(1234, config, &mut first_buffer);
+        let mut second = Game::new(1234, config, &mut second_buffer);
+        first.step(None);
+        second.step(None);
+        for row in 0..BOARD_HEIGHT {
+            for col in 0..BOARD_WIDTH {
+                let position = Position { row, col };
+                assert_eq!(first.board().get(position), second.board().get(position));
+            }
+        }
+    }
+
+    #[test]
+    fn restart_with_same_seed_replays_food_placement() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            levels: FOOD_AHEAD,
+            foods_per_level: 100,
+            ..Config::default()
+        };
+        let mut game = Game::new(1234, config, &mut buffer);
+        assert_eq!(game.seed(), 1234);
+        assert_eq!(game.step(None).ate_food[0], Some(FoodKind::Regular));
+        let food = food_position(game.board());
+        game.restart(99);
+        assert_eq!(game.seed(), 99);
+        game.restart(1234);
+        assert_eq!(game.step(None).ate_food[0], Some(FoodKind::Regular));
+        assert_eq!(food_position(game.board()), food);
+    }
+
+    #[test]
+    fn eating_enough_food_moves_on_to_next_level() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            levels: FOOD_AHEAD,
+            foods_per_level: 1,
+            ..Config::default()
+        };
+        let mut game = Game::new(1, config, &mut buffer);
+        assert_eq!(game.level_number(), 1);
+        let events = game.step(None);
+        assert_eq!(events.ate_food[0], Some(FoodKind::Regular));
+        assert!(events.level_up);
+        assert_eq!(game.level_number(), 2);
+        assert_eq!(game.level().name, "WALLED");
+        // Score carries over, while the snake starts over at the level's position
+        assert_eq!(game.score().get_score(), 1);
+        assert_eq!(game.snake().head(), Position { row: 3, col: 3 });
+        assert_eq!(game.snake().direction(), Direction::Right);
+        assert_eq!(game.snake().length(), config.start_length);
+        assert_eq!(game.board().get(Position { row: 9, col: 9 }), Cell::Wall);
+        assert_eq!(food_count(game.board()), 1);
+    }
+
+    #[test]
+    fn last_level_is_played_until_the_end() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            levels: FOOD_AHEAD,
+            start_level: 42,
+            foods_per_level: 1,
+            ..Config::default()
+        };
+        let mut game = Game::new(1, config, &mut buffer);
+        assert_eq!(game.level_number(), 2);
+        for _ in 0..20 {
+            assert!(!game.step(None).level_up);
+        }
+    }
+
+    #[test]
+    fn food_is_never_placed_on_walls() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            levels: &level::LEVELS[4..],
+            ..Config::default()
+        };
+        let mut game = Game::new(99, config, &mut buffer);
+        for _ in 0..100 {
+            let position = game.random_empty_cell().unwrap();
+            game.board.set(position, Cell::Food(FoodKind::Regular));
+        }
+        let level = game.level();
+        for wall in level.walls {
+            for row in wall.top_left.row..(wall.top_left.row + wall.height) {
+                for col in wall.top_left.col..(wall.top_left.col + wall.width) {
+                    assert_eq!(game.board().get(Position { row, col }), Cell::Wall);
+                }
+            }
+        }
+        assert_eq!(food_count(game.board()), 101);
+    }
+
+    #[test]
+    fn levels_can_be_played_without_walls() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            levels: FOOD_AHEAD,
+            start_level: 1,
+            walls: false,
+            ..Config::default()
+        };
+        let mut game = Game::new(1, config, &mut buffer);
+        assert_eq!(game.board().get(Position { row: 9, col: 9 }), Cell::Empty);
+        game.restart(1);
+        assert_eq!(game.board().get(Position { row: 9, col: 9 }), Cell::Empty);
+    }
+
+    #[test]
+    fn filling_the_board_wins_the_game() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            levels: SINGLE_ROW,
+            edges: Edges::Wrap,
+            foods_per_level: u16::MAX,
+            special_foods: false,
+            ..Config::default()
+        };
+        let mut game = Game::new(5, config, &mut buffer);
+        let mut steps = 0;
+        while game.state() == State::Playing {
+            let events = game.step(None);
+            assert!(!events.collided[0]);
+            // Food is always somewhere ahead on the row
+            assert_eq!(food_count(game.board()), if events.won { 0 } else { 1 });
+            steps += 1;
+            assert!(steps < BOARD_WIDTH * BOARD_WIDTH);
+        }
+        assert_eq!(game.state(), State::Won);
+        assert_eq!(game.snake().length(), BOARD_WIDTH);
+        assert_eq!(
+            game.score().get_score() as usize,
+            BOARD_WIDTH - config.start_length
+        );
+        assert_eq!(game.board().empty_cells(), 0);
+    }
+
+    #[test]
+    fn special_foods_apply_their_effects() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            levels: FOOD_AHEAD,
+            foods_per_level: 100,
+            special_foods: false,
+            ..Config::default()
+        };
+        let mut game = Game::new(1, config, &mut buffer);
+        assert_eq!(game.step(None).ate_food[0], Some(FoodKind::Regular));
+        assert_eq!(game.snake().length(), 4);
+
+        let ahead = game.snake().head().step(Direction::Left).unwrap();
+        put_special_food(&mut game, FoodKind::Slim, ahead);
+        assert_eq!(game.step(None).ate_food[0], Some(FoodKind::Slim));
+        assert_eq!(game.snake().length(), config.start_length);
+        assert_eq!(game.score().get_score(), 3);
+        assert_eq!(game.special_food(), None);
+
+        let ahead = game.snake().head().step(Direction::Left).unwrap();
+        put_special_food(&mut game, FoodKind::Speed, ahead);
+        game.step(None);
+        assert!(game.speed().is_boosted());
+        assert_eq!(game.score().get_score(), 6);
+
+        let ahead = game.snake().head().step(Direction::Left).unwrap();
+        put_special_food(&mut game, FoodKind::Bonus, ahead);
+        game.step(None);
+        assert_eq!(game.score().get_score(), 11);
+        assert_eq!(game.snake().length(), config.start_length + 2);
+    }
+
+    #[test]
+    fn special_food_disappears_after_its_steps() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            levels: FOOD_AHEAD,
+            foods_per_level: 100,
+            edges: Edges::Wrap,
+            special_foods: false,
+            ..Config::default()
+        };
+        let mut game = Game::new(1, config, &mut buffer);
+        let corner = Position { row: 0, col: 0 };
+        put_special_food(&mut game, FoodKind::Bonus, corner);
+        for _ in 1..food::SPECIAL_FOOD_STEPS {
+            assert!(!game.step(None).special_food_expired);
+        }
+        assert_eq!(game.board().get(corner), Cell::Food(FoodKind::Bonus));
+        assert!(game.step(None).special_food_expired);
+        assert_eq!(game.board().get(corner), Cell::Empty);
+        assert_eq!(game.special_food(), None);
+    }
+
+    #[test]
+    fn special_foods_show_up_now_and_then() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            levels: SINGLE_ROW,
+            edges: Edges::Wrap,
+            foods_per_level: u16::MAX,
+            ..Config::default()
+        };
+        let mut game = Game::new(5, config, &mut buffer);
+        let mut regular_foods = 0;
+        while game.special_food().is_none() {
+            if game.step(None).ate_food[0].is_some() {
+                regular_foods += 1;
+            }
+            assert!(regular_foods < 40);
+        }
+        let special_food = *game.special_food().unwrap();
+        assert_ne!(special_food.kind, FoodKind::Regular);
+        assert_eq!(
+            game.board().get(special_food.position),
+            Cell::Food(special_food.kind)
+        );
+        assert_eq!(food_count(game.board()), 1);
+    }
+
+    #[test]
+    fn start_level_applies_after_restart() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            levels: FOOD_AHEAD,
+            ..Config::default()
+        };
+        let mut game = Game::new(1, config, &mut buffer);
+        game.set_start_level(1);
+        assert_eq!(game.level_number(), 1);
+        game.restart(1);
+        assert_eq!(game.level_number(), 2);
+        assert_eq!(game.snake().head(), Position { row: 3, col: 3 });
+        assert_eq!(game.board().get(Position { row: 8, col: 8 }), Cell::Wall);
+    }
+
+    #[test]
+    fn qualifying_score_asks_for_initials() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            levels: FOOD_AHEAD,
+            ..Config::default()
+        };
+        let mut game = Game::new(3, config, &mut buffer);
+        while game.state() == State::Playing {
+            assert_eq!(game.initials(), None);
+            game.step(None);
+        }
+        let score = game.score().get_score();
+        assert!(score > 0);
+        game.enter_initial('j');
+        game.enter_initial('d');
+        assert!(!game.submit_initials());
+        game.enter_initial('x');
+        assert_eq!(game.initials().unwrap().as_str(), "JDX");
+        assert!(game.submit_initials());
+        assert_eq!(game.initials(), None);
+        assert_eq!(game.high_scores().entries()[0].initials(), "JDX");
+        assert_eq!(game.high_scores().entries()[0].score, score);
+
+        // Restarting gives up on initials not typed yet
+        game.restart(3);
+        while game.state() == State::Playing {
+            game.step(None);
+        }
+        assert!(game.initials().is_some());
+        let replay = game.replay().clone();
+        game.restart(3);
+        assert_eq!(game.initials(), None);
+        assert_eq!(game.high_scores().entries().len(), 1);
+
+        // Skipping gives up on initials while the game stays over
+        game.restart(3);
+        while game.state() == State::Playing {
+            game.step(None);
+        }
+        game.enter_initial('A');
+        game.skip_initials();
+        assert_eq!(game.initials(), None);
+        assert!(game.is_over());
+        assert!(!game.submit_initials());
+        assert_eq!(game.high_scores().entries().len(), 1);
+
+        // Watching the game again asks for no initials
+        game.play_replay(replay);
+        while game.state() == State::Playing {
+            game.step(None);
+        }
+        assert_eq!(game.score().get_score(), score);
+        assert_eq!(game.initials(), None);
+
+        // Scoring nothing never makes it into the table
+        game.restart(3);
+        game.step(Some(Direction::Up));
+        while game.state() == State::Playing {
+            game.step(None);
+        }
+        assert_eq!(game.score().get_score(), 0);
+        assert_eq!(game.initials(), None);
+    }
+
+    fn two_player_config() -> Config {
+        Config {
+            levels: FOOD_AHEAD,
+            foods_per_level: 100,
+            special_foods: false,
+            players: 2,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn second_player_starts_opposite_and_scores_separately() {
+        let mut buffer = body_buffer();
+        let mut game = Game::new(1, two_player_config(), &mut buffer);
+        assert_eq!(game.players(), 2);
+        let second_start = Position {
+            row: BOARD_HEIGHT - 1 - CENTRE.row,
+            col: BOARD_WIDTH - 1 - CENTRE.col,
+        };
+        assert_eq!(game.snake_of(1).head(), second_start);
+        assert_eq!(game.snake_of(1).direction(), Direction::Right);
+        assert_eq!(game.snake_of(1).positions().count(), 3);
+
+        let events = game.step(None);
+        assert_eq!(events.ate_food, [Some(FoodKind::Regular), None]);
+        assert_eq!(game.score_of(0).get_score(), 1);
+        assert_eq!(game.score_of(1).get_score(), 0);
+        assert_eq!(
+            game.snake_of(1).head(),
+            second_start.step(Direction::Right).unwrap()
+        );
+        // Second player steers its own snake only
+        game.set_player_turn_direction(1, Direction::Up);
+        game.step(None);
+        assert_eq!(game.snake_of(0).direction(), Direction::Left);
+        assert_eq!(game.snake_of(1).direction(), Direction::Up);
+    }
+
+    #[test]
+    fn both_snakes_eating_on_the_same_step_move_on_a_single_level() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            foods_per_level: 1,
+            ..two_player_config()
+        };
+        let mut game = Game::new(1, config, &mut buffer);
+        let second_food = game.snake_of(1).head().step(Direction::Right).unwrap();
+        game.board.set(second_food, Cell::Food(FoodKind::Regular));
+        let events = game.step(None);
+        assert_eq!(
+            events.ate_food,
+            [Some(FoodKind::Regular), Some(FoodKind::Regular)]
+        );
+        assert!(events.level_up);
+        assert_eq!(game.level_number(), 2);
+        assert_eq!(game.level_foods, 0);
+        assert_eq!(food_count(game.board()), 1);
+        // Both snakes still score
+        assert_eq!(game.score_of(0).get_score(), 1);
+        assert_eq!(game.score_of(1).get_score(), 1);
+    }
+
+    #[test]
+    fn heads_meeting_in_one_cell_is_a_draw() {
+        let mut buffer = body_buffer();
+        let mut game = Game::new(1, two_player_config(), &mut buffer);
+        let second_start = game.snake_of(1).head();
+        // Both heads move into the food in front of the first snake
+        game.set_player_turn_direction(1, Direction::Down);
+        let events = game.step(None);
+        assert_eq!(events.collided, [true, true]);
+        assert_eq!(events.ate_food, [None, None]);
+        assert_eq!(game.state(), State::GameOver);
+        assert_eq!(game.winner(), None);
+        assert_eq!(game.snake_of(0).head(), CENTRE);
+        assert_eq!(game.snake_of(1).head(), second_start);
+    }
+
+    #[test]
+    fn running_into_other_snake_loses_despite_higher_score() {
+        let mut buffer = body_buffer();
+        let mut game = Game::new(1, two_player_config(), &mut buffer);
+        game.step(None);
+        game.scores[1] = Score::new(10);
+        // Second snake turns into the body the first one left behind
+        game.set_player_turn_direction(1, Direction::Down);
+        let events = game.step(None);
+        assert_eq!(events.collided, [false, true]);
+        assert_eq!(game.state(), State::GameOver);
+        assert_eq!(game.winner(), Some(0));
+        // High scores are kept for single player games
+        assert_eq!(game.initials(), None);
+    }
+
+    #[test]
+    fn number_of_players_applies_after_restart() {
+        let mut buffer = body_buffer();
+        let mut game = Game::new(1, Config::default(), &mut buffer);
+        assert_eq!(game.players(), 1);
+        assert!(!game.snake_of(1).is_on_board());
+        game.set_player_turn_direction(1, Direction::Up);
+        game.set_players(2);
+        game.step(None);
+        assert!(!game.snake_of(1).is_on_board());
+
+        game.restart(1);
+        assert_eq!(game.players(), 2);
+        assert!(game.snake_of(1).is_on_board());
+        let second_head = game.snake_of(1).head();
+        assert_eq!(game.board().get(second_head), Cell::Head(Direction::Right));
+
+        game.set_players(1);
+        game.restart(1);
+        assert!(!game.snake_of(1).is_on_board());
+        assert_eq!(game.board().get(second_head), Cell::Empty);
+    }
+
+    #[test]
+    fn computer_steers_second_snake() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            opponent: Some(Difficulty::Survival),
+            ..two_player_config()
+        };
+        let mut game = Game::new(1, config, &mut buffer);
+        assert!(game.is_computer(1));
+        assert!(!game.is_computer(0));
+        let mut steps = 0;
+        while game.state() == State::Playing {
+            // Keys pressed for the computer's snake are ignored
+            game.set_player_turn_direction(1, Direction::Up);
+            let events = game.step(None);
+            assert!(!events.collided[1]);
+            steps += 1;
+        }
+        // First snake runs into the left edge, while the computer's one keeps clear of trouble
+        assert_eq!(steps, CENTRE.col + 1);
+        assert_eq!(game.winner(), Some(1));
+
+        game.set_players(1);
+        game.restart(1);
+        assert_eq!(game.opponent(), None);
+    }
+
+    /// Positions of every snake's cells, to tell two games apart
+    fn snake_positions(game: &Game) -> [[Position; 64]; MAX_PLAYERS] {
+        let mut positions = [[Position::default(); 64]; MAX_PLAYERS];
+        for (player, cells) in positions.iter_mut().enumerate() {
+            for (cell, position) in cells.iter_mut().zip(game.snake_of(player).positions()) {
+                *cell = position;
+            }
+        }
+        positions
+    }
+
+    #[test]
+    fn replay_plays_the_same_game_again() {
+        let mut buffer = body_buffer();
+        let config = Config {
+            start_speed: 9,
+            edges: Edges::Wrap,
+            players: 2,
+            opponent: Some(Difficulty::SafePath),
+            ..Config::default()
+        };
+        let mut game = Game::new(5, config, &mut buffer);
+        let mut ticks = 0;
+        while game.state() == State::Playing && ticks < 5000 {
+            // Turns are pressed between steps, as keys would be
+            if ticks % 3 == 0 {
+                if let Some(turn_direction) =
+                    autopilot::choose_direction(game.board(), game.snake(), Difficulty::Greedy)
+                {
+                    game.set_turn_direction(turn_direction);
+                }
+            }
+            game.tick();
+            ticks += 1;
+        }
+        assert!(game.replay().turns().count() > 10);
+        assert!(game.replay().is_complete());
+        let replay = Replay::decode(game.replay().as_bytes()).unwrap();
+
+        let mut replay_buffer = body_buffer();
+        let mut replayed = Game::new(1, Config::default(), &mut replay_buffer);
+        replayed.play_replay(replay);
+        assert!(replayed.is_replaying());
+        assert_eq!(replayed.seed(), 5);
+        assert_eq!(replayed.players(), 2);
+        while replayed.state() == State::Playing && replayed.steps < game.steps {
+            // Keys pressed while watching change nothing
+            replayed.set_turn_direction(Direction::Down);
+            replayed.step(None);
+        }
+        assert_eq!(
+            (replayed.steps, replayed.state()),
+            (game.steps, game.state())
+        );
+        assert_eq!(replayed.score_of(0), game.score_of(0));
+        assert_eq!(replayed.score_of(1), game.score_of(1));
+        assert_eq!(replayed.winner(), game.winner());
+        assert_eq!(snake_positions(&replayed), snake_positions(&game));
+        assert!(replayed.replay().turns().eq(game.replay().turns()));
+
+        // Options of the game watched do not stick
+        assert_eq!(replayed.config().players, 1);
+        replayed.set_start_speed(2);
+        replayed.restart(1);
+        assert!(!replayed.is_replaying());
+        assert_eq!(replayed.players(), 1);
+        assert_eq!(replayed.speed().level(), 2);
+        assert_eq!(replayed.board().edges(), Edges::Solid);
+    }
+}
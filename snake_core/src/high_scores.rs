@@ -0,0 +1,239 @@
+/* Table of best scores with the initials of players who made them
+//
+// The table knows how to encode itself into a small block of bytes with a
+// checksum, so the kernel can keep it in whatever storage survives a reboot
+// without this module caring where the bytes end up.
+*/
+
+/// Number of entries kept in the table
+pub const TABLE_SIZE: usize = 5;
+/// Number of letters in player's initials
+pub const INITIALS_LENGTH: usize = 3;
+/// Number of bytes in encoded table: magic and count, entries, then checksum
+pub const ENCODED_SIZE: usize = 2 + TABLE_SIZE * (INITIALS_LENGTH + 2) + 1;
+// Marks bytes as a table encoded by this version of the format
+const MAGIC: u8 = 0x5A;
+
+/// Score along with initials of the player who made it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    /// Upper case ASCII letters
+    pub initials: [u8; INITIALS_LENGTH],
+    pub score: u16,
+}
+
+impl Entry {
+    /// Initials as a printable string
+    pub fn initials(&self) -> &str {
+        core::str::from_utf8(&self.initials).unwrap_or("???")
+    }
+}
+
+/// Best scores, highest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HighScores {
+    entries: [Entry; TABLE_SIZE],
+    len: usize,
+}
+
+impl HighScores {
+    /// Create an empty table
+    pub fn new() -> Self {
+        HighScores {
+            entries: [Entry {
+                initials: [b' '; INITIALS_LENGTH],
+                score: 0,
+            }; TABLE_SIZE],
+            len: 0,
+        }
+    }
+
+    /// Get entries in the table, highest score first
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries[..self.len]
+    }
+
+    /// Check if given score would make it into the table
+    pub fn qualifies(&self, score: u16) -> bool {
+        score > 0 && (self.len < TABLE_SIZE || score > self.entries[TABLE_SIZE - 1].score)
+    }
+
+    /// Add entry to the table, dropping the lowest one if full. Return its rank counting from 0, or None if it did not qualify.
+    pub fn insert(&mut self, entry: Entry) -> Option<usize> {
+        if !self.qualifies(entry.score) {
+            return None;
+        }
+        // Later entries with an equal score rank below the earlier ones
+        let mut rank = self.len;
+        while rank > 0 && self.entries[rank - 1].score < entry.score {
+            rank -= 1;
+        }
+        if self.len < TABLE_SIZE {
+            self.len += 1;
+        }
+        for i in ((rank + 1)..self.len).rev() {
+            self.entries[i] = self.entries[i - 1];
+        }
+        self.entries[rank] = entry;
+        Some(rank)
+    }
+
+    /// Encode the table into bytes ending with a checksum
+    pub fn encode(&self) -> [u8; ENCODED_SIZE] {
+        let mut bytes = [0; ENCODED_SIZE];
+        bytes[0] = MAGIC;
+        bytes[1] = self.len as u8;
+        for (i, entry) in self.entries().iter().enumerate() {
+            let offset = 2 + i * (INITIALS_LENGTH + 2);
+            bytes[offset..(offset + INITIALS_LENGTH)].copy_from_slice(&entry.initials);
+            bytes[offset + INITIALS_LENGTH] = (entry.score >> 8) as u8;
+            bytes[offset + INITIALS_LENGTH + 1] = entry.score as u8;
+        }
+        bytes[ENCODED_SIZE - 1] = checksum(&bytes[..(ENCODED_SIZE - 1)]);
+        bytes
+    }
+
+    /// Decode a table from bytes, or return None if they do not hold a valid one
+    pub fn decode(bytes: &[u8; ENCODED_SIZE]) -> Option<Self> {
+        if bytes[0] != MAGIC || bytes[ENCODED_SIZE - 1] != checksum(&bytes[..(ENCODED_SIZE - 1)]) {
+            return None;
+        }
+        let len = bytes[1] as usize;
+        if len > TABLE_SIZE {
+            return None;
+        }
+        let mut table = HighScores::new();
+        for i in 0..len {
+            let offset = 2 + i * (INITIALS_LENGTH + 2);
+            let mut initials = [0; INITIALS_LENGTH];
+            initials.copy_from_slice(&bytes[offset..(offset + INITIALS_LENGTH)]);
+            if !initials.iter().all(u8::is_ascii_uppercase) {
+                return None;
+            }
+            let score = ((bytes[offset + INITIALS_LENGTH] as u16) << 8)
+                | bytes[offset + INITIALS_LENGTH + 1] as u16;
+            table.entries[i] = Entry { initials, score };
+        }
+        table.len = len;
+        Some(table)
+    }
+}
+
+impl Default for HighScores {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Byte making all bytes of the block add up to zero
+fn checksum(bytes: &[u8]) -> u8 {
+    let sum = bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    0u8.wrapping_sub(sum)
+}
+
+/// Initials being typed in by the player
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Initials {
+    letters: [u8; INITIALS_LENGTH],
+    len: usize,
+}
+
+impl Initials {
+    /// Add a letter, in upper case. Anything but ASCII letters is ignored, and so is a letter past the last one.
+    pub fn push(&mut self, letter: char) {
+        if letter.is_ascii_alphabetic() && self.len < INITIALS_LENGTH {
+            self.letters[self.len] = letter.to_ascii_uppercase() as u8;
+            self.len += 1;
+        }
+    }
+
+    /// Remove the last letter typed
+    pub fn pop(&mut self) {
+        if self.len > 0 {
+            self.len -= 1;
+        }
+    }
+
+    /// Check if every letter has been typed
+    pub fn is_complete(&self) -> bool {
+        self.len == INITIALS_LENGTH
+    }
+
+    /// Letters typed so far
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.letters[..self.len]).unwrap_or("")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(initials: &[u8; INITIALS_LENGTH], score: u16) -> Entry {
+        Entry {
+            initials: *initials,
+            score,
+        }
+    }
+
+    #[test]
+    fn table_keeps_best_scores_in_order() {
+        let mut table = HighScores::new();
+        assert!(!table.qualifies(0));
+        assert_eq!(table.insert(entry(b"AAA", 10)), Some(0));
+        assert_eq!(table.insert(entry(b"BBB", 30)), Some(0));
+        assert_eq!(table.insert(entry(b"CCC", 10)), Some(2));
+        assert_eq!(table.insert(entry(b"DDD", 20)), Some(1));
+        assert_eq!(table.insert(entry(b"EEE", 5)), Some(4));
+        assert!(!table.qualifies(5));
+        assert_eq!(table.insert(entry(b"FFF", 5)), None);
+        assert_eq!(table.insert(entry(b"GGG", 15)), Some(2));
+        let scores: [u16; TABLE_SIZE] = [30, 20, 15, 10, 10];
+        assert_eq!(table.entries().len(), TABLE_SIZE);
+        for (entry, score) in table.entries().iter().zip(scores.iter()) {
+            assert_eq!(entry.score, *score);
+        }
+        assert_eq!(table.entries()[3].initials(), "AAA");
+    }
+
+    #[test]
+    fn encoded_table_decodes_to_itself() {
+        let mut table = HighScores::new();
+        table.insert(entry(b"ABC", 300));
+        table.insert(entry(b"XYZ", 7));
+        let bytes = table.encode();
+        assert_eq!(HighScores::decode(&bytes), Some(table));
+        assert_eq!(
+            HighScores::decode(&HighScores::new().encode()),
+            Some(HighScores::new())
+        );
+    }
+
+    #[test]
+    fn corrupted_bytes_are_rejected() {
+        let mut table = HighScores::new();
+        table.insert(entry(b"ABC", 300));
+        let mut bytes = table.encode();
+        bytes[3] ^= 0x01;
+        assert_eq!(HighScores::decode(&bytes), None);
+        // Storage that was never written to
+        assert_eq!(HighScores::decode(&[0; ENCODED_SIZE]), None);
+        assert_eq!(HighScores::decode(&[0xFF; ENCODED_SIZE]), None);
+    }
+
+    #[test]
+    fn initials_take_three_letters_in_upper_case() {
+        let mut initials = Initials::default();
+        initials.push('a');
+        initials.push('1');
+        initials.push('b');
+        assert_eq!(initials.as_str(), "AB");
+        assert!(!initials.is_complete());
+        initials.pop();
+        initials.push('x');
+        initials.push('Y');
+        initials.push('Z');
+        assert_eq!(initials.as_str(), "AXY");
+        assert!(initials.is_complete());
+    }
+}
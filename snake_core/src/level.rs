@@ -0,0 +1,212 @@
+/* Built-in levels with obstacles inside the play field
+//
+// A level is a layout of walls placed on an empty board, along with where the
+// snake and the first food start. Levels are compiled in as constants, and the
+// game moves on to the next one after enough food is eaten.
+*/
+
+use crate::board::{Board, Cell, Position, BOARD_HEIGHT, BOARD_WIDTH};
+use crate::snake::Direction;
+
+/// Food to eat on a level before moving on to the next one, unless configured otherwise
+pub const FOODS_PER_LEVEL: u16 = 10;
+
+/// Rectangular block of wall cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wall {
+    /// Position of the block's top-left cell
+    pub top_left: Position,
+    /// Number of rows covered by the block
+    pub height: usize,
+    /// Number of columns covered by the block
+    pub width: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Level {
+    /// Name shown to the player
+    pub name: &'static str,
+    /// Obstacles placed on the board, which have to fit inside it
+    pub walls: &'static [Wall],
+    /// Position of first player's snake's head when the level starts
+    pub start_position: Position,
+    /// Direction the snake moves in when the level starts
+    pub start_direction: Direction,
+    /// Position of food when the level starts
+    pub food_start_position: Position,
+}
+
+impl Level {
+    /// Put walls of the level on the board
+    pub fn place_walls(&self, board: &mut Board) {
+        for wall in self.walls {
+            for row in wall.top_left.row..(wall.top_left.row + wall.height) {
+                for col in wall.top_left.col..(wall.top_left.col + wall.width) {
+                    board.set(Position { row, col }, Cell::Wall);
+                }
+            }
+        }
+    }
+
+    /// Position of the head and direction of given player's snake when the level starts. The second
+    /// player starts opposite the first one across the centre of the board, heading the other way.
+    pub fn start(&self, player: usize) -> (Position, Direction) {
+        if player == 0 {
+            (self.start_position, self.start_direction)
+        } else {
+            let position = Position {
+                row: BOARD_HEIGHT - 1 - self.start_position.row,
+                col: BOARD_WIDTH - 1 - self.start_position.col,
+            };
+            (position, self.start_direction.opposite())
+        }
+    }
+}
+
+/// Levels in the order they are played
+pub const LEVELS: &[Level] = &[
+    Level {
+        name: "OPEN FIELD",
+        walls: &[],
+        start_position: Position {
+            row: BOARD_HEIGHT / 2,
+            col: BOARD_WIDTH / 2,
+        },
+        start_direction: Direction::Left,
+        food_start_position: Position { row: 1, col: 18 },
+    },
+    Level {
+        name: "TWO BARS",
+        walls: &[
+            Wall {
+                top_left: Position { row: 5, col: 14 },
+                height: 1,
+                width: 50,
+            },
+            Wall {
+                top_left: Position { row: 16, col: 14 },
+                height: 1,
+                width: 50,
+            },
+        ],
+        start_position: Position { row: 11, col: 39 },
+        start_direction: Direction::Left,
+        food_start_position: Position { row: 1, col: 18 },
+    },
+    Level {
+        name: "PILLARS",
+        walls: &[
+            Wall {
+                top_left: Position { row: 3, col: 19 },
+                height: 6,
+                width: 2,
+            },
+            Wall {
+                top_left: Position { row: 3, col: 57 },
+                height: 6,
+                width: 2,
+            },
+            Wall {
+                top_left: Position { row: 13, col: 19 },
+                height: 6,
+                width: 2,
+            },
+            Wall {
+                top_left: Position { row: 13, col: 57 },
+                height: 6,
+                width: 2,
+            },
+        ],
+        start_position: Position { row: 11, col: 39 },
+        start_direction: Direction::Up,
+        food_start_position: Position { row: 1, col: 18 },
+    },
+    Level {
+        name: "CROSS",
+        walls: &[
+            Wall {
+                top_left: Position { row: 2, col: 39 },
+                height: 18,
+                width: 1,
+            },
+            Wall {
+                top_left: Position { row: 11, col: 8 },
+                height: 1,
+                width: 31,
+            },
+            Wall {
+                top_left: Position { row: 11, col: 40 },
+                height: 1,
+                width: 30,
+            },
+        ],
+        start_position: Position { row: 5, col: 20 },
+        start_direction: Direction::Right,
+        food_start_position: Position { row: 16, col: 58 },
+    },
+    Level {
+        name: "CORRIDORS",
+        walls: &[
+            Wall {
+                top_left: Position { row: 0, col: 15 },
+                height: 16,
+                width: 1,
+            },
+            Wall {
+                top_left: Position { row: 6, col: 31 },
+                height: 16,
+                width: 1,
+            },
+            Wall {
+                top_left: Position { row: 0, col: 47 },
+                height: 16,
+                width: 1,
+            },
+            Wall {
+                top_left: Position { row: 6, col: 63 },
+                height: 16,
+                width: 1,
+            },
+        ],
+        start_position: Position { row: 19, col: 5 },
+        start_direction: Direction::Right,
+        food_start_position: Position { row: 2, col: 70 },
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Config, MAX_PLAYERS};
+    use crate::snake::Snake;
+
+    #[test]
+    fn built_in_levels_leave_room_for_snakes_and_food() {
+        let start_length = Config::default().start_length;
+        for level in LEVELS {
+            for wall in level.walls {
+                assert!(wall.top_left.row + wall.height <= BOARD_HEIGHT);
+                assert!(wall.top_left.col + wall.width <= BOARD_WIDTH);
+            }
+            let mut board = Board::default();
+            level.place_walls(&mut board);
+            assert_eq!(board.get(level.food_start_position), Cell::Empty);
+            let walls: usize = level.walls.iter().map(|w| w.height * w.width).sum();
+            for player in 0..MAX_PLAYERS {
+                let (head, direction) = level.start(player);
+                // Spawning over a wall or another snake would overwrite it
+                let mut buffer = [Position::default(); 8];
+                let snake = Snake::new(&mut buffer, head, direction, start_length, &mut board);
+                assert_eq!(snake.length(), start_length);
+                let expected = walls + (player + 1) * start_length;
+                assert_eq!(board.empty_cells(), BOARD_HEIGHT * BOARD_WIDTH - expected);
+            }
+            for player in 0..MAX_PLAYERS {
+                // Snakes can move ahead without crashing straight away
+                let (head, direction) = level.start(player);
+                let ahead = board.neighbour(head, direction).unwrap();
+                assert_eq!(board.get(ahead), Cell::Empty, "{}", level.name);
+            }
+        }
+    }
+}
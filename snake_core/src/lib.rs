@@ -0,0 +1,24 @@
+/* Platform independent core of the snake game
+//
+// Holds the board model and game rules without touching any hardware, so that
+// the kernel can drive the game from interrupts while the same logic can be
+// simulated and tested on the host with `cargo test`.
+*/
+
+#![no_std]
+
+pub mod autopilot;
+pub mod bindings;
+pub mod board;
+pub mod food;
+pub mod game;
+pub mod high_scores;
+pub mod level;
+pub mod options;
+pub mod prng;
+pub mod record;
+pub mod replay;
+pub mod ring_buffer;
+pub mod score;
+pub mod snake;
+pub mod speed;
@@ -0,0 +1,200 @@
+/* Options for starting the game, given as words of a command line
+//
+// Every option is a `name=value` word, for instance `speed=7 mode=wrap`. Words
+// are applied one at a time in order, so a later word overrides an earlier one,
+// and a word that is not understood leaves the options as they were.
+//
+// Options:
+//   speed=1..9      speed level games start at
+//   seed=N|daily    seed for food placement of every game, instead of a new
+//                   random one for each game. `daily` derives it from
+//                   the date, so everyone playing on the same day gets the same food.
+//   mode=wrap|solid whether the snake passes through edges of the board
+//   level=N         level games start at, counting from 1
+//   walls=on|off    whether levels are played with their walls
+//   players=1|2     number of players sharing the keyboard
+//   opponent=greedy|safe|survival|off
+//                   difficulty of the computer steering the second snake,
+//                   which makes games two-player ones unless `off`
+//   serial=on|off   whether the game logs what happens to serial port
+*/
+
+use crate::autopilot::Difficulty;
+use crate::board::Edges;
+use crate::game::{Config, MAX_PLAYERS};
+use crate::speed;
+
+/// Reasons a word of the command line cannot be applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionError {
+    /// Word is not in `name=value` form, or names no known option
+    UnknownOption,
+    /// Value is not one the option accepts
+    InvalidValue,
+}
+
+/// Where games take the seed for food placement from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Seed {
+    /// A new random seed for every game
+    Random,
+    /// Same seed for every game
+    Fixed(u64),
+    /// Seed derived from today's date
+    Daily,
+}
+
+/// Options the game is started with
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// Configuration of new games
+    pub config: Config,
+    /// Where seeds for food placement come from
+    pub seed: Seed,
+    /// Whether to log to serial port
+    pub serial: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            config: Config::default(),
+            seed: Seed::Random,
+            serial: true,
+        }
+    }
+}
+
+impl Options {
+    /// Apply a single `name=value` word of the command line
+    pub fn apply(&mut self, word: &str) -> Result<(), OptionError> {
+        let mut parts = word.splitn(2, '=');
+        let name = parts.next().unwrap_or("");
+        let value = parts.next().ok_or(OptionError::UnknownOption)?;
+        match name {
+            "speed" => {
+                let level = value.parse().map_err(|_| OptionError::InvalidValue)?;
+                if !(speed::MIN_LEVEL..=speed::MAX_LEVEL).contains(&level) {
+                    return Err(OptionError::InvalidValue);
+                }
+                self.config.start_speed = level;
+            }
+            "seed" => {
+                self.seed = match value {
+                    "daily" => Seed::Daily,
+                    _ => Seed::Fixed(value.parse().map_err(|_| OptionError::InvalidValue)?),
+                };
+            }
+            "mode" => {
+                self.config.edges = match value {
+                    "wrap" => Edges::Wrap,
+                    "solid" => Edges::Solid,
+                    _ => return Err(OptionError::InvalidValue),
+                };
+            }
+            "level" => {
+                let number: usize = value.parse().map_err(|_| OptionError::InvalidValue)?;
+                if !(1..=self.config.levels.len()).contains(&number) {
+                    return Err(OptionError::InvalidValue);
+                }
+                self.config.start_level = number - 1;
+            }
+            "walls" => self.config.walls = parse_switch(value)?,
+            "players" => {
+                let players = value.parse().map_err(|_| OptionError::InvalidValue)?;
+                if !(1..=MAX_PLAYERS).contains(&players) {
+                    return Err(OptionError::InvalidValue);
+                }
+                self.config.players = players;
+            }
+            "opponent" => {
+                self.config.opponent = match value {
+                    "greedy" => Some(Difficulty::Greedy),
+                    "safe" => Some(Difficulty::SafePath),
+                    "survival" => Some(Difficulty::Survival),
+                    "off" => None,
+                    _ => return Err(OptionError::InvalidValue),
+                };
+                if self.config.opponent.is_some() {
+                    self.config.players = MAX_PLAYERS;
+                }
+            }
+            "serial" => self.serial = parse_switch(value)?,
+            _ => return Err(OptionError::UnknownOption),
+        }
+        Ok(())
+    }
+}
+
+/// Parse value of an option turning something on or off
+fn parse_switch(value: &str) -> Result<bool, OptionError> {
+    match value {
+        "on" => Ok(true),
+        "off" => Ok(false),
+        _ => Err(OptionError::InvalidValue),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(command_line: &str) -> Options {
+        let mut options = Options::default();
+        for word in command_line.split_whitespace() {
+            options.apply(word).unwrap();
+        }
+        options
+    }
+
+    #[test]
+    fn options_change_config() {
+        let options = parse("speed=7 seed=1234 mode=wrap level=3 walls=off players=2 serial=off");
+        assert_eq!(options.config.start_speed, 7);
+        assert_eq!(options.seed, Seed::Fixed(1234));
+        assert_eq!(parse("seed=daily").seed, Seed::Daily);
+        let options_with_opponent = parse("opponent=safe");
+        assert_eq!(
+            options_with_opponent.config.opponent,
+            Some(Difficulty::SafePath)
+        );
+        assert_eq!(options_with_opponent.config.players, 2);
+        assert_eq!(options.config.edges, Edges::Wrap);
+        assert_eq!(options.config.start_level, 2);
+        assert!(!options.config.walls);
+        assert_eq!(options.config.players, 2);
+        assert!(!options.serial);
+    }
+
+    #[test]
+    fn no_options_keep_defaults() {
+        let options = parse("");
+        assert_eq!(options.config.start_speed, speed::DEFAULT_LEVEL);
+        assert_eq!(options.seed, Seed::Random);
+        assert_eq!(options.config.edges, Edges::Solid);
+        assert!(options.config.walls);
+        assert!(options.serial);
+    }
+
+    #[test]
+    fn invalid_words_are_rejected_without_changes() {
+        let mut options = Options::default();
+        assert_eq!(options.apply("speed=0"), Err(OptionError::InvalidValue));
+        assert_eq!(options.apply("speed=fast"), Err(OptionError::InvalidValue));
+        assert_eq!(options.apply("level=0"), Err(OptionError::InvalidValue));
+        assert_eq!(options.apply("level=99"), Err(OptionError::InvalidValue));
+        assert_eq!(options.apply("mode=spiral"), Err(OptionError::InvalidValue));
+        assert_eq!(options.apply("players=3"), Err(OptionError::InvalidValue));
+        assert_eq!(
+            options.apply("opponent=hard"),
+            Err(OptionError::InvalidValue)
+        );
+        assert_eq!(options.apply("seed=-1"), Err(OptionError::InvalidValue));
+        assert_eq!(options.apply("colour=red"), Err(OptionError::UnknownOption));
+        assert_eq!(options.apply("quiet"), Err(OptionError::UnknownOption));
+        assert_eq!(options.config.start_speed, speed::DEFAULT_LEVEL);
+        assert_eq!(options.config.start_level, 0);
+        assert_eq!(options.config.players, 1);
+        assert_eq!(options.seed, Seed::Random);
+    }
+}
@@ -0,0 +1,243 @@
+/* Pseudo random number generators for placing food
+//
+// `Rng` is implemented by two small generators with 64 bits of state:
+// xorshift64* and PCG-XSH-RR. Both are fast, deterministic for a given seed,
+// and pass the statistical tests below, unlike the 32-bit LCG used before,
+// whose low bits repeat with a short period.
+// Based upon https://www.pcg-random.org/ and https://vigna.di.unimi.it/ftp/papers/xorshift.pdf
+*/
+
+/// Source of pseudo random numbers
+pub trait Rng {
+    /// Generate the next 64 random bits
+    fn next_u64(&mut self) -> u64;
+
+    /// Generate a random number in `0..bound`, every value equally likely. `bound` must not be 0.
+    fn gen_range(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "empty range");
+        // Rejecting the values below 2^64 % bound leaves a multiple of bound values to take the remainder of
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let value = self.next_u64();
+            if value >= threshold {
+                return value % bound;
+            }
+        }
+    }
+}
+
+/// Generator used by the game
+pub type DefaultRng = Pcg32;
+
+/// xorshift64* generator, scrambling a xorshift state with a multiplication
+#[derive(Copy, Debug, Clone)]
+pub struct Xorshift64Star {
+    state: u64,
+}
+
+impl Xorshift64Star {
+    /// Create a generator from given seed
+    pub fn new(seed: u64) -> Self {
+        // State of all zeros would stay zero forever
+        let state = if seed == 0 {
+            0x9E37_79B9_7F4A_7C15
+        } else {
+            seed
+        };
+        Xorshift64Star { state }
+    }
+}
+
+impl Rng for Xorshift64Star {
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
+
+// Multiplier and default stream of the PCG reference implementation
+const PCG_MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+const PCG_DEFAULT_STREAM: u64 = 0xDA3E_39CB_94B9_5BDB;
+
+/// PCG-XSH-RR generator, producing 32 bits per step of a 64-bit LCG
+#[derive(Copy, Debug, Clone)]
+pub struct Pcg32 {
+    state: u64,
+    /// Odd increment selecting one of the generator's streams
+    increment: u64,
+}
+
+impl Pcg32 {
+    /// Create a generator from given seed
+    pub fn new(seed: u64) -> Self {
+        Pcg32::with_stream(seed, PCG_DEFAULT_STREAM)
+    }
+
+    /// Create a generator from given seed, on one of 2^63 streams giving different sequences
+    pub fn with_stream(seed: u64, stream: u64) -> Self {
+        let mut pcg = Pcg32 {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        pcg.step();
+        pcg.state = pcg.state.wrapping_add(seed);
+        pcg.step();
+        pcg
+    }
+
+    /// Advance the underlying LCG
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(PCG_MULTIPLIER)
+            .wrapping_add(self.increment);
+    }
+
+    /// Generate the next 32 random bits
+    pub fn next_u32(&mut self) -> u32 {
+        let state = self.state;
+        self.step();
+        // Permute the old state's high bits, which are the most random ones of an LCG
+        let xor_shifted = (((state >> 18) ^ state) >> 27) as u32;
+        let rotation = (state >> 59) as u32;
+        xor_shifted.rotate_right(rotation)
+    }
+}
+
+impl Rng for Pcg32 {
+    fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Chi-squared value exceeded with a probability of 0.1% for 15 degrees of freedom
+    const CHI_SQUARED_CRITICAL_15: f64 = 37.7;
+    const SAMPLES: usize = 160_000;
+
+    /// Chi-squared statistic of how far counts are from all being equal
+    fn chi_squared(counts: &[usize]) -> f64 {
+        let total: usize = counts.iter().sum();
+        let expected = total as f64 / counts.len() as f64;
+        counts
+            .iter()
+            .map(|count| {
+                let difference = *count as f64 - expected;
+                difference * difference / expected
+            })
+            .sum()
+    }
+
+    /// Check that values of `gen_range` over 16 buckets are spread evenly
+    fn check_uniform_range(rng: &mut dyn Rng) {
+        let mut counts = [0; 16];
+        for _ in 0..SAMPLES {
+            counts[rng.gen_range(16) as usize] += 1;
+        }
+        assert!(chi_squared(&counts) < CHI_SQUARED_CRITICAL_15);
+    }
+
+    /// Check that the low 4 bits of consecutive values are spread evenly, which they are not for a plain LCG
+    fn check_uniform_low_bits(rng: &mut dyn Rng) {
+        let mut counts = [0; 16];
+        for _ in 0..SAMPLES {
+            counts[(rng.next_u64() & 0xF) as usize] += 1;
+        }
+        assert!(chi_squared(&counts) < CHI_SQUARED_CRITICAL_15);
+    }
+
+    /// Check that every bit is set in about half of the values
+    fn check_bit_balance(rng: &mut dyn Rng) {
+        let mut counts = [0; 64];
+        for _ in 0..SAMPLES {
+            let value = rng.next_u64();
+            for (bit, count) in counts.iter_mut().enumerate() {
+                *count += (value >> bit) as usize & 1;
+            }
+        }
+        // Standard deviation of each count is sqrt(SAMPLES / 4) = 200, so this is 5 of them
+        for count in counts.iter() {
+            assert!((*count as i64 - SAMPLES as i64 / 2).abs() < 1000);
+        }
+    }
+
+    /// Check that pairs of consecutive values, as used for row and column, cover a grid evenly
+    fn check_uniform_pairs(rng: &mut dyn Rng) {
+        let mut counts = [0; 16];
+        for _ in 0..SAMPLES {
+            let row = rng.gen_range(4);
+            let col = rng.gen_range(4);
+            counts[(row * 4 + col) as usize] += 1;
+        }
+        assert!(chi_squared(&counts) < CHI_SQUARED_CRITICAL_15);
+    }
+
+    #[test]
+    fn xorshift_is_uniform() {
+        let mut rng = Xorshift64Star::new(42);
+        check_uniform_range(&mut rng);
+        check_uniform_low_bits(&mut rng);
+        check_bit_balance(&mut rng);
+        check_uniform_pairs(&mut rng);
+    }
+
+    #[test]
+    fn pcg_is_uniform() {
+        let mut rng = Pcg32::new(42);
+        check_uniform_range(&mut rng);
+        check_uniform_low_bits(&mut rng);
+        check_bit_balance(&mut rng);
+        check_uniform_pairs(&mut rng);
+    }
+
+    #[test]
+    fn pcg_matches_reference_output() {
+        // First outputs of the reference implementation's pcg32-demo, seeded with 42 on stream 54
+        let mut rng = Pcg32::with_stream(42, 54);
+        assert_eq!(rng.next_u32(), 0xA15C_02B7);
+        assert_eq!(rng.next_u32(), 0x7B47_F409);
+        assert_eq!(rng.next_u32(), 0xBA1D_3330);
+    }
+
+    #[test]
+    fn same_seed_gives_same_sequence() {
+        let mut first = Pcg32::new(42);
+        let mut second = Pcg32::new(42);
+        let mut other = Pcg32::new(43);
+        let mut differences = 0;
+        for _ in 0..100 {
+            let value = first.next_u64();
+            assert_eq!(value, second.next_u64());
+            if value != other.next_u64() {
+                differences += 1;
+            }
+        }
+        assert_eq!(differences, 100);
+    }
+
+    #[test]
+    fn zero_seed_does_not_get_stuck() {
+        let mut rng = Xorshift64Star::new(0);
+        assert_ne!(rng.next_u64(), rng.next_u64());
+    }
+
+    #[test]
+    fn range_stays_within_bound() {
+        let mut rng = Xorshift64Star::new(7);
+        for bound in 1..100 {
+            for _ in 0..100 {
+                assert!(rng.gen_range(bound) < bound);
+            }
+        }
+        // Nearly half of all values are rejected for this bound, and the rest must still be in range
+        let bound = (1 << 63) + 1;
+        for _ in 0..100 {
+            assert!(rng.gen_range(bound) < bound);
+        }
+    }
+}
@@ -0,0 +1,217 @@
+/* On-disk format for data kept across reboots
+//
+// The disk is split into fixed areas, one per kind of record. An area starts
+// with a header naming the kind and version of the record, the length of its
+// payload and a checksum of it, and the payload follows right after. What the
+// payload holds is up to the owner of the record, which can tell old layouts
+// apart by the version.
+//
+// Header layout, multi-byte values little endian:
+//   0..4   magic "SNKR"
+//   4      kind
+//   5      version of payload's layout
+//   6..8   payload length in bytes
+//   8..10  Fletcher-16 checksum of payload
+//   10..12 reserved, zero
+*/
+
+/// Number of bytes in a disk sector
+pub const SECTOR_SIZE: usize = 512;
+/// Number of bytes in a record header
+pub const HEADER_SIZE: usize = 12;
+// Marks the start of a record
+const MAGIC: [u8; 4] = *b"SNKR";
+
+/// Kinds of records, each kept in its own area of the disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordKind {
+    HighScores,
+    Settings,
+    Replay,
+}
+
+impl RecordKind {
+    /// Identifier of the kind stored in the header
+    fn id(self) -> u8 {
+        match self {
+            RecordKind::HighScores => 1,
+            RecordKind::Settings => 2,
+            RecordKind::Replay => 3,
+        }
+    }
+
+    /// First sector of the area holding records of this kind
+    pub fn first_sector(self) -> u32 {
+        match self {
+            RecordKind::HighScores => 0,
+            RecordKind::Settings => 1,
+            RecordKind::Replay => 2,
+        }
+    }
+
+    /// Number of sectors in the area holding records of this kind
+    pub fn sectors(self) -> u32 {
+        match self {
+            RecordKind::HighScores | RecordKind::Settings => 1,
+            RecordKind::Replay => 62,
+        }
+    }
+
+    /// Largest payload fitting in the area along with the header
+    pub fn capacity(self) -> usize {
+        self.sectors() as usize * SECTOR_SIZE - HEADER_SIZE
+    }
+}
+
+/// Reasons a record cannot be stored or read back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordError {
+    /// Area does not start with a record header, for instance because nothing was saved yet
+    NoRecord,
+    /// Area holds a record of another kind
+    WrongKind,
+    /// Payload does not fit in the area or in the buffer given to read it into
+    TooLong,
+    /// Payload does not match its checksum
+    Corrupted,
+}
+
+/// Description of a record's payload stored in front of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    pub kind: RecordKind,
+    /// Version of payload's layout
+    pub version: u8,
+    /// Payload length in bytes
+    pub length: u16,
+    pub checksum: u16,
+}
+
+impl Header {
+    /// Describe given payload of a record
+    pub fn new(kind: RecordKind, version: u8, payload: &[u8]) -> Result<Self, RecordError> {
+        if payload.len() > kind.capacity() {
+            return Err(RecordError::TooLong);
+        }
+        Ok(Header {
+            kind,
+            version,
+            length: payload.len() as u16,
+            checksum: checksum(payload),
+        })
+    }
+
+    /// Encode the header into bytes to be stored in front of payload
+    pub fn encode(&self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0; HEADER_SIZE];
+        bytes[0..4].copy_from_slice(&MAGIC);
+        bytes[4] = self.kind.id();
+        bytes[5] = self.version;
+        bytes[6..8].copy_from_slice(&self.length.to_le_bytes());
+        bytes[8..10].copy_from_slice(&self.checksum.to_le_bytes());
+        bytes
+    }
+
+    /// Decode header of a record expected to be of given kind
+    pub fn decode(kind: RecordKind, bytes: &[u8; HEADER_SIZE]) -> Result<Self, RecordError> {
+        if bytes[0..4] != MAGIC {
+            return Err(RecordError::NoRecord);
+        }
+        if bytes[4] != kind.id() {
+            return Err(RecordError::WrongKind);
+        }
+        let length = u16::from_le_bytes([bytes[6], bytes[7]]);
+        if length as usize > kind.capacity() {
+            return Err(RecordError::Corrupted);
+        }
+        Ok(Header {
+            kind,
+            version: bytes[5],
+            length,
+            checksum: u16::from_le_bytes([bytes[8], bytes[9]]),
+        })
+    }
+
+    /// Check payload read back against the header
+    pub fn verify(&self, payload: &[u8]) -> Result<(), RecordError> {
+        if payload.len() == self.length as usize && checksum(payload) == self.checksum {
+            Ok(())
+        } else {
+            Err(RecordError::Corrupted)
+        }
+    }
+}
+
+/// Fletcher-16 checksum of given bytes
+pub fn checksum(bytes: &[u8]) -> u16 {
+    let mut sum1: u16 = 0;
+    let mut sum2: u16 = 0;
+    for byte in bytes {
+        sum1 = (sum1 + *byte as u16) % 255;
+        sum2 = (sum2 + sum1) % 255;
+    }
+    (sum2 << 8) | sum1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_decodes_to_itself() {
+        let payload = [1, 2, 3, 4, 5];
+        let header = Header::new(RecordKind::Settings, 2, &payload).unwrap();
+        let decoded = Header::decode(RecordKind::Settings, &header.encode()).unwrap();
+        assert_eq!(decoded, header);
+        assert_eq!(decoded.verify(&payload), Ok(()));
+        assert_eq!(
+            decoded.verify(&[1, 2, 3, 4, 6]),
+            Err(RecordError::Corrupted)
+        );
+        assert_eq!(decoded.verify(&payload[..4]), Err(RecordError::Corrupted));
+    }
+
+    #[test]
+    fn foreign_bytes_are_not_a_record() {
+        let header = Header::new(RecordKind::HighScores, 1, &[42]).unwrap();
+        assert_eq!(
+            Header::decode(RecordKind::Replay, &header.encode()),
+            Err(RecordError::WrongKind)
+        );
+        assert_eq!(
+            Header::decode(RecordKind::HighScores, &[0; HEADER_SIZE]),
+            Err(RecordError::NoRecord)
+        );
+    }
+
+    #[test]
+    fn payload_has_to_fit_in_its_area() {
+        let payload = [0; SECTOR_SIZE];
+        assert_eq!(
+            Header::new(RecordKind::HighScores, 1, &payload),
+            Err(RecordError::TooLong)
+        );
+        assert!(Header::new(RecordKind::Replay, 1, &payload).is_ok());
+    }
+
+    #[test]
+    fn areas_do_not_overlap() {
+        let kinds = [
+            RecordKind::HighScores,
+            RecordKind::Settings,
+            RecordKind::Replay,
+        ];
+        for pair in kinds.windows(2) {
+            assert_eq!(
+                pair[0].first_sector() + pair[0].sectors(),
+                pair[1].first_sector()
+            );
+        }
+    }
+
+    #[test]
+    fn checksum_detects_swapped_bytes() {
+        assert_eq!(checksum(b"abcde"), 0xC8F0);
+        assert_ne!(checksum(&[1, 2]), checksum(&[2, 1]));
+    }
+}
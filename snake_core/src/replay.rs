@@ -0,0 +1,399 @@
+/* Recordings of games, to play them again
+//
+// Games are deterministic: food is placed from a seeded generator, and the
+// computer only looks at the board to choose its turns. A replay therefore
+// holds no more than the seed, the options the game started with and the turns
+// players queued, each along with the step it was queued before. Playing it
+// back queues the same turns before the same steps, and the game unfolds
+// exactly as it did.
+//
+// Layout, multi-byte values little endian:
+//   0      version of layout
+//   1..9   seed
+//   9      starting speed level
+//   10     index of starting level
+//   11     flags: wrapping edges, walls, special foods, turns left out
+//   12     number of players
+//   13     computer opponent, 0 for none or 1 + index in DIFFICULTIES
+//   14..   turns
+//
+// A turn takes a single byte while players keep pressing keys: the direction
+// in bits 0..2, the player in bit 2, and steps since the previous turn in bits
+// 3..8. Steps of 31 or more set those bits to 31, and the rest of them follow
+// in LEB128 bytes.
+*/
+
+use crate::autopilot::DIFFICULTIES;
+use crate::board::Edges;
+use crate::game::{Config, MAX_PLAYERS};
+use crate::snake::Direction;
+use crate::speed;
+
+/// Version of the layout, stored in its first byte
+pub const VERSION: u8 = 1;
+/// Most bytes a replay takes. Turns past it are not recorded.
+pub const REPLAY_CAPACITY: usize = 4096;
+// Bytes in front of the turns
+const HEADER_SIZE: usize = 14;
+// Flags of options stored in the header
+const WRAP_FLAG: u8 = 1;
+const WALLS_FLAG: u8 = 1 << 1;
+const SPECIAL_FOODS_FLAG: u8 = 1 << 2;
+// Set once a turn did not fit, so that the recording ends before the game did
+const TRUNCATED_FLAG: u8 = 1 << 3;
+// Steps since the previous turn from which on they no longer fit in the turn's byte
+const LONG_STEPS: u32 = 31;
+
+/// Turn of a player's snake, queued before given step of the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Turn {
+    /// Number of steps taken when the turn was queued
+    pub step: u32,
+    pub player: usize,
+    pub direction: Direction,
+}
+
+/// Position of the next turn to play back from a replay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    /// Offset of the turn's first byte
+    offset: usize,
+    /// Step of the turn before it
+    step: u32,
+}
+
+/// Seed, options and turns of a game
+#[derive(Clone)]
+pub struct Replay {
+    bytes: [u8; REPLAY_CAPACITY],
+    /// Number of bytes used, header included
+    length: usize,
+    /// Step of the last turn recorded
+    last_step: u32,
+    /// Whether a turn did not fit, ending the recording early
+    truncated: bool,
+}
+
+impl Replay {
+    /// Start recording a game started from given seed and configuration
+    pub fn new(seed: u64, config: &Config) -> Self {
+        let mut bytes = [0; REPLAY_CAPACITY];
+        bytes[0] = VERSION;
+        bytes[1..9].copy_from_slice(&seed.to_le_bytes());
+        bytes[9] = config.start_speed;
+        bytes[10] = config.first_level() as u8;
+        let flags = [
+            (config.edges == Edges::Wrap, WRAP_FLAG),
+            (config.walls, WALLS_FLAG),
+            (config.special_foods, SPECIAL_FOODS_FLAG),
+        ];
+        bytes[11] = flags
+            .iter()
+            .filter(|(set, _)| *set)
+            .fold(0, |bits, (_, flag)| bits | flag);
+        bytes[12] = config.player_count() as u8;
+        bytes[13] = match config.opponent {
+            None => 0,
+            Some(difficulty) => {
+                1 + DIFFICULTIES
+                    .iter()
+                    .position(|other| *other == difficulty)
+                    .unwrap_or(0) as u8
+            }
+        };
+        Replay {
+            bytes,
+            length: HEADER_SIZE,
+            last_step: 0,
+            truncated: false,
+        }
+    }
+
+    /// Get the seed the game started from
+    pub fn seed(&self) -> u64 {
+        let mut seed = [0; 8];
+        seed.copy_from_slice(&self.bytes[1..9]);
+        u64::from_le_bytes(seed)
+    }
+
+    /// Configuration the game started with. Levels and other settings no boot option changes are
+    /// taken from `base`.
+    pub fn config(&self, base: Config) -> Config {
+        let flags = self.bytes[11];
+        Config {
+            start_speed: self.bytes[9],
+            start_level: self.bytes[10] as usize,
+            edges: if flags & WRAP_FLAG != 0 {
+                Edges::Wrap
+            } else {
+                Edges::Solid
+            },
+            walls: flags & WALLS_FLAG != 0,
+            special_foods: flags & SPECIAL_FOODS_FLAG != 0,
+            players: self.bytes[12] as usize,
+            opponent: match self.bytes[13] {
+                0 => None,
+                index => DIFFICULTIES.get(index as usize - 1).copied(),
+            },
+            ..base
+        }
+    }
+
+    /// Record a turn. Steps of turns must not go down. Once a turn does not fit, no more are recorded.
+    pub fn record(&mut self, turn: Turn) {
+        if self.truncated {
+            return;
+        }
+        let mut encoded = [0; 6];
+        let length = encode_turn(turn, turn.step - self.last_step, &mut encoded);
+        if self.length + length > REPLAY_CAPACITY {
+            self.truncated = true;
+            self.bytes[11] |= TRUNCATED_FLAG;
+            return;
+        }
+        self.bytes[self.length..(self.length + length)].copy_from_slice(&encoded[..length]);
+        self.length += length;
+        self.last_step = turn.step;
+    }
+
+    /// Check if every turn of the game was recorded
+    pub fn is_complete(&self) -> bool {
+        !self.truncated
+    }
+
+    /// Cursor at the first turn
+    pub fn start(&self) -> Cursor {
+        Cursor {
+            offset: HEADER_SIZE,
+            step: 0,
+        }
+    }
+
+    /// Get the turn at given cursor and move the cursor past it, or None after the last turn
+    pub fn next_turn(&self, cursor: &mut Cursor) -> Option<Turn> {
+        let (turn, length) = decode_turn(&self.bytes[cursor.offset..self.length], cursor.step)?;
+        cursor.offset += length;
+        cursor.step = turn.step;
+        Some(turn)
+    }
+
+    /// Iterate over recorded turns in order
+    pub fn turns<'r>(&'r self) -> impl Iterator<Item = Turn> + 'r {
+        let mut cursor = self.start();
+        core::iter::from_fn(move || self.next_turn(&mut cursor))
+    }
+
+    /// Get the bytes the replay is stored and exported as
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.length]
+    }
+
+    /// Decode a replay from bytes, or return None if they do not hold a valid one
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < HEADER_SIZE || bytes.len() > REPLAY_CAPACITY || bytes[0] != VERSION {
+            return None;
+        }
+        let valid_header = (speed::MIN_LEVEL..=speed::MAX_LEVEL).contains(&bytes[9])
+            && bytes[11] & !(WRAP_FLAG | WALLS_FLAG | SPECIAL_FOODS_FLAG | TRUNCATED_FLAG) == 0
+            && (1..=MAX_PLAYERS).contains(&(bytes[12] as usize))
+            && bytes[13] as usize <= DIFFICULTIES.len();
+        if !valid_header {
+            return None;
+        }
+        let mut replay = Replay {
+            bytes: [0; REPLAY_CAPACITY],
+            length: bytes.len(),
+            last_step: 0,
+            truncated: bytes[11] & TRUNCATED_FLAG != 0,
+        };
+        replay.bytes[..bytes.len()].copy_from_slice(bytes);
+        // Every byte must belong to a turn of one of the players
+        let mut cursor = replay.start();
+        while let Some(turn) = replay.next_turn(&mut cursor) {
+            if turn.player >= bytes[12] as usize {
+                return None;
+            }
+        }
+        if cursor.offset != replay.length {
+            return None;
+        }
+        replay.last_step = cursor.step;
+        Some(replay)
+    }
+}
+
+/// Code of a direction in a turn's first byte
+fn direction_code(direction: Direction) -> u8 {
+    match direction {
+        Direction::Left => 0,
+        Direction::Right => 1,
+        Direction::Up => 2,
+        Direction::Down => 3,
+    }
+}
+
+/// Encode a turn taken given steps after the previous one into `bytes`, returning the number of bytes used
+fn encode_turn(turn: Turn, steps: u32, bytes: &mut [u8; 6]) -> usize {
+    let steps_bits = steps.min(LONG_STEPS) as u8;
+    bytes[0] = direction_code(turn.direction) | (turn.player as u8) << 2 | steps_bits << 3;
+    if steps < LONG_STEPS {
+        return 1;
+    }
+    let mut rest = steps - LONG_STEPS;
+    let mut length = 1;
+    loop {
+        let low_bits = (rest & 0x7F) as u8;
+        rest >>= 7;
+        if rest == 0 {
+            bytes[length] = low_bits;
+            return length + 1;
+        }
+        bytes[length] = low_bits | 0x80;
+        length += 1;
+    }
+}
+
+/// Decode the turn at the start of `bytes` given the step of the turn before it, returning it along
+/// with the number of bytes it took. None if `bytes` do not start with a whole turn.
+fn decode_turn(bytes: &[u8], previous_step: u32) -> Option<(Turn, usize)> {
+    let first = *bytes.first()?;
+    let direction = match first & 0b11 {
+        0 => Direction::Left,
+        1 => Direction::Right,
+        2 => Direction::Up,
+        _ => Direction::Down,
+    };
+    let player = (first >> 2 & 1) as usize;
+    let mut steps = (first >> 3) as u32;
+    let mut length = 1;
+    if steps == LONG_STEPS {
+        let mut shift = 0;
+        loop {
+            let byte = *bytes.get(length)?;
+            length += 1;
+            if shift > 28 {
+                return None;
+            }
+            steps = steps.checked_add(((byte & 0x7F) as u32) << shift)?;
+            if byte & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+        }
+    }
+    let turn = Turn {
+        step: previous_step.checked_add(steps)?,
+        player,
+        direction,
+    };
+    Some((turn, length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::autopilot::Difficulty;
+
+    fn turn(step: u32, player: usize, direction: Direction) -> Turn {
+        Turn {
+            step,
+            player,
+            direction,
+        }
+    }
+
+    #[test]
+    fn options_and_seed_survive_encoding() {
+        let config = Config {
+            start_speed: 7,
+            start_level: 2,
+            edges: Edges::Wrap,
+            walls: false,
+            players: 2,
+            opponent: Some(Difficulty::Survival),
+            ..Config::default()
+        };
+        let replay = Replay::decode(Replay::new(0xDEAD_BEEF_0123, &config).as_bytes()).unwrap();
+        assert_eq!(replay.seed(), 0xDEAD_BEEF_0123);
+        let decoded = replay.config(Config::default());
+        assert_eq!(decoded.start_speed, 7);
+        assert_eq!(decoded.start_level, 2);
+        assert_eq!(decoded.edges, Edges::Wrap);
+        assert!(!decoded.walls);
+        assert!(decoded.special_foods);
+        assert_eq!(decoded.players, 2);
+        assert_eq!(decoded.opponent, Some(Difficulty::Survival));
+    }
+
+    #[test]
+    fn turns_take_a_byte_unless_far_apart() {
+        let config = Config {
+            players: 2,
+            ..Config::default()
+        };
+        let mut replay = Replay::new(1, &config);
+        let turns = [
+            turn(0, 0, Direction::Up),
+            turn(0, 1, Direction::Left),
+            turn(30, 0, Direction::Right),
+            turn(61, 1, Direction::Down),
+            turn(100_000, 0, Direction::Up),
+        ];
+        for turn in turns.iter() {
+            replay.record(*turn);
+        }
+        // Turn 31 steps after the one before takes a second byte, and 99_939 steps take three more
+        assert_eq!(replay.as_bytes().len(), HEADER_SIZE + 3 + 2 + 4);
+        assert!(replay.turns().eq(turns.iter().copied()));
+        let decoded = Replay::decode(replay.as_bytes()).unwrap();
+        assert!(decoded.turns().eq(turns.iter().copied()));
+        assert_eq!(decoded.last_step, 100_000);
+    }
+
+    #[test]
+    fn recording_stops_when_full() {
+        let mut replay = Replay::new(1, &Config::default());
+        for step in 0..(REPLAY_CAPACITY - HEADER_SIZE) as u32 {
+            replay.record(turn(step, 0, Direction::Up));
+        }
+        assert!(replay.is_complete());
+        replay.record(turn(REPLAY_CAPACITY as u32, 0, Direction::Down));
+        replay.record(turn(REPLAY_CAPACITY as u32, 0, Direction::Left));
+        assert!(!replay.is_complete());
+        assert_eq!(replay.as_bytes().len(), REPLAY_CAPACITY);
+        assert_eq!(replay.turns().count(), REPLAY_CAPACITY - HEADER_SIZE);
+
+        // Saved and loaded again, the replay still tells it is not complete
+        let decoded = Replay::decode(replay.as_bytes()).unwrap();
+        assert!(!decoded.is_complete());
+        assert_eq!(decoded.seed(), replay.seed());
+        assert!(
+            Replay::decode(Replay::new(1, &Config::default()).as_bytes())
+                .unwrap()
+                .is_complete()
+        );
+    }
+
+    #[test]
+    fn invalid_bytes_are_rejected() {
+        let mut replay = Replay::new(1, &Config::default());
+        replay.record(turn(40, 0, Direction::Up));
+        let bytes = replay.as_bytes();
+        assert!(Replay::decode(bytes).is_some());
+        // Turn cut off in the middle of its steps
+        assert!(Replay::decode(&bytes[..(bytes.len() - 1)]).is_none());
+        assert!(Replay::decode(&bytes[..(HEADER_SIZE - 1)]).is_none());
+        let mut wrong_version = [0; HEADER_SIZE];
+        wrong_version.copy_from_slice(&bytes[..HEADER_SIZE]);
+        wrong_version[0] = VERSION + 1;
+        assert!(Replay::decode(&wrong_version).is_none());
+        let mut too_many_players = wrong_version;
+        too_many_players[0] = VERSION;
+        too_many_players[12] = MAX_PLAYERS as u8 + 1;
+        assert!(Replay::decode(&too_many_players).is_none());
+        // Turn of a second player in a single player game
+        let mut missing_player = Replay::new(1, &Config::default());
+        missing_player.record(turn(40, 1, Direction::Up));
+        assert!(Replay::decode(missing_player.as_bytes()).is_none());
+    }
+}
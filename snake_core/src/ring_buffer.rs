@@ -0,0 +1,186 @@
+/* Generic RingBuffer implementation */
+
+use core::marker::PhantomData;
+
+/// Thread unsafe RingBuffer over an array
+#[derive(Debug)]
+pub struct RingBuffer<T, B> {
+    /// Array owned by the ring buffer, or a slice lent by its owner, so the core never has to allocate memory
+    buffer: B,
+    /// Invariant: The valid elements in buffer are in range [first, last)
+    first: usize,
+    last: usize,
+    /// True when buffer is full. False otherwise.
+    full: bool,
+    element: PhantomData<T>,
+}
+
+impl<T, B: AsRef<[T]> + AsMut<[T]>> RingBuffer<T, B> {
+    /// Create a new ring buffer utilizing provided array as buffer space
+    pub fn new(buffer: B) -> Self {
+        RingBuffer {
+            buffer,
+            first: 0,
+            last: 0,
+            full: false,
+            element: PhantomData,
+        }
+    }
+
+    /// Maximum number of elements the buffer can hold
+    pub fn capacity(&self) -> usize {
+        self.buffer.as_ref().len()
+    }
+
+    /// Check if ring buffer is completely empty
+    pub fn is_empty(&self) -> bool {
+        self.first == self.last && !self.full
+    }
+
+    /// Check if ring buffer is completely filled up
+    pub fn is_full(&self) -> bool {
+        self.first == self.last && self.full
+    }
+
+    /// Number of elements currently in the buffer
+    pub fn len(&self) -> usize {
+        if self.full {
+            self.capacity()
+        } else {
+            (self.last + self.capacity() - self.first) % self.capacity()
+        }
+    }
+
+    /// Remove all elements from the buffer
+    pub fn clear(&mut self) {
+        self.first = 0;
+        self.last = 0;
+        self.full = false;
+    }
+
+    /// Put an element at the beginning of the buffer, logically shifting existing elements to the right in the process
+    pub fn prepend(&mut self, element: T) {
+        assert!(!self.is_full());
+        // May have overflow
+        let new_first_pos = (self.first + self.capacity() - 1) % self.capacity();
+        self.buffer.as_mut()[new_first_pos] = element;
+        self.first = new_first_pos;
+        if new_first_pos == self.last {
+            self.full = true;
+        }
+    }
+
+    /// Put an element at the end of the buffer
+    pub fn append(&mut self, element: T) {
+        assert!(!self.is_full());
+        let new_last_pos = (self.last + 1) % self.capacity();
+        self.buffer.as_mut()[self.last] = element;
+        self.last = new_last_pos;
+        if new_last_pos == self.first {
+            self.full = true;
+        }
+    }
+
+    /// Take a peek at the first element in the buffer
+    pub fn peek_first(&self) -> &T {
+        assert!(!self.is_empty());
+        &self.buffer.as_ref()[self.first]
+    }
+
+    /// Take a peek at the last element in the buffer
+    pub fn peek_last(&self) -> &T {
+        assert!(!self.is_empty());
+        // May have overflow
+        &self.buffer.as_ref()[(self.last + self.capacity() - 1) % self.capacity()]
+    }
+
+    /// Take a look at the i'th element in the buffer
+    pub fn peek_ith(&self, i: usize) -> &T {
+        assert!(!self.is_empty());
+        assert!(i < self.capacity());
+        let index = (self.first + i) % self.capacity();
+        &self.buffer.as_ref()[index]
+    }
+
+    /// Remove and return the first element from the buffer, logically shifting other existing elements to left
+    pub fn pop_first(&mut self) -> &T {
+        assert!(!self.is_empty());
+        let element = &self.buffer.as_ref()[self.first];
+        self.first = (self.first + 1) % self.capacity();
+        self.full = false;
+        element
+    }
+
+    /// Remove and return the last element from the buffer
+    pub fn pop_last(&mut self) -> &T {
+        assert!(!self.is_empty());
+        // May have overflow
+        self.last = (self.last + self.capacity() - 1) % self.capacity();
+        self.full = false;
+        &self.buffer.as_ref()[self.last]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append_and_prepend_keep_order() {
+        let mut array = [0; 4];
+        let mut ring_buffer = RingBuffer::new(&mut array);
+        assert!(ring_buffer.is_empty());
+        ring_buffer.append(2);
+        ring_buffer.append(3);
+        ring_buffer.prepend(1);
+        assert_eq!(ring_buffer.len(), 3);
+        assert_eq!(*ring_buffer.peek_first(), 1);
+        assert_eq!(*ring_buffer.peek_ith(1), 2);
+        assert_eq!(*ring_buffer.peek_last(), 3);
+    }
+
+    #[test]
+    fn pops_wrap_around_the_array() {
+        let mut array = [0; 3];
+        let mut ring_buffer = RingBuffer::new(&mut array);
+        for i in 0..10 {
+            ring_buffer.prepend(i);
+            if ring_buffer.len() == 3 {
+                assert_eq!(*ring_buffer.pop_last(), i - 2);
+            }
+        }
+        assert_eq!(*ring_buffer.pop_first(), 9);
+        assert_eq!(*ring_buffer.pop_first(), 8);
+        assert!(ring_buffer.is_empty());
+    }
+
+    #[test]
+    fn fills_up_to_capacity() {
+        let mut array = [0; 3];
+        let mut ring_buffer = RingBuffer::new(&mut array);
+        ring_buffer.append(1);
+        ring_buffer.append(2);
+        ring_buffer.prepend(0);
+        assert!(ring_buffer.is_full());
+        assert_eq!(ring_buffer.len(), 3);
+        assert_eq!(*ring_buffer.pop_last(), 2);
+        assert!(!ring_buffer.is_full());
+    }
+
+    #[test]
+    fn owns_array_storage() {
+        let mut ring_buffer = RingBuffer::new([0u8; 2]);
+        assert_eq!(ring_buffer.capacity(), 2);
+        ring_buffer.append(7);
+        assert_eq!(*ring_buffer.pop_first(), 7);
+    }
+
+    #[test]
+    #[should_panic]
+    fn append_to_full_buffer_panics() {
+        let mut array = [0; 1];
+        let mut ring_buffer = RingBuffer::new(&mut array);
+        ring_buffer.append(1);
+        ring_buffer.append(2);
+    }
+}
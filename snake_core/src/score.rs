@@ -0,0 +1,48 @@
+/* Score kept by the player during the game */
+
+use crate::food::FoodKind;
+
+const INCREMENT: u16 = 1;
+const BONUS_POINTS: u16 = 5;
+const SLIM_POINTS: u16 = 2;
+const SPEED_POINTS: u16 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    // Under current board size, score will be < 2000 from regular food, and
+    // special items cannot take it anywhere near u16::MAX
+    value: u16,
+}
+
+impl Score {
+    pub fn new(initial_score: u16) -> Self {
+        Score {
+            value: initial_score,
+        }
+    }
+
+    /// Increment the score value
+    pub fn increment(&mut self) {
+        self.value += INCREMENT;
+    }
+
+    /// Add points for eating given kind of food
+    pub fn eat(&mut self, kind: FoodKind) {
+        self.value = self.value.saturating_add(points(kind));
+    }
+
+    /// Get the current score
+    pub fn get_score(&self) -> u16 {
+        self.value
+    }
+}
+
+/// Points scored for eating given kind of food
+pub fn points(kind: FoodKind) -> u16 {
+    match kind {
+        FoodKind::Regular => INCREMENT,
+        FoodKind::Bonus => BONUS_POINTS,
+        FoodKind::Slim => SLIM_POINTS,
+        FoodKind::Speed => SPEED_POINTS,
+    }
+}
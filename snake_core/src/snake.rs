@@ -0,0 +1,298 @@
+/* Module for all snake related functionality for the game
+//
+// Assumption: Snake has minimum size of 3
+*/
+
+use crate::board::{Board, BodyShape, Cell, Position, BOARD_HEIGHT, BOARD_WIDTH};
+use crate::food::FoodKind;
+use crate::ring_buffer::RingBuffer;
+
+/// Snake can fill up every cell of the board
+pub const MAX_SNAKE_SIZE: usize = BOARD_HEIGHT * BOARD_WIDTH;
+// Number of turns remembered between ticks. Further key presses are dropped.
+const TURN_QUEUE_SIZE: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    /// Direction pointing the other way
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+        }
+    }
+}
+
+/// Outcome of moving the snake by one cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    /// Snake moved into an empty cell
+    Moved,
+    /// Snake moved into a cell with food of given kind and grew
+    Ate(FoodKind),
+    /// Snake ran into the boundary, a wall or a body. Snake is left where it was.
+    Collided,
+}
+
+pub struct Snake<'s> {
+    /// Current co-ordinates of body of the snake
+    body: RingBuffer<Position, &'s mut [Position]>,
+    /// Current direction the snake is moving in
+    direction: Direction,
+    /// Directions to follow on upcoming ticks, one per tick, in the order keys were pressed
+    turn_directions: RingBuffer<Direction, [Direction; TURN_QUEUE_SIZE]>,
+}
+
+impl<'s> Snake<'s> {
+    /// Create a snake with its head at given position and body trailing behind it, and place it on the board
+    pub fn new(
+        buffer: &'s mut [Position],
+        head: Position,
+        direction: Direction,
+        length: usize,
+        board: &mut Board,
+    ) -> Self {
+        let mut snake = Snake::with_buffer(buffer);
+        snake.respawn(head, direction, length, board);
+        snake
+    }
+
+    /// Create a snake that is not on the board, until placed there with `respawn`
+    pub fn with_buffer(buffer: &'s mut [Position]) -> Self {
+        Snake {
+            body: RingBuffer::new(buffer),
+            direction: Direction::Left,
+            turn_directions: RingBuffer::new([Direction::Left; TURN_QUEUE_SIZE]),
+        }
+    }
+
+    /// Start over with head at given position and body trailing behind it, and place the snake on the board
+    pub fn respawn(
+        &mut self,
+        head: Position,
+        direction: Direction,
+        length: usize,
+        board: &mut Board,
+    ) {
+        self.body.clear();
+        self.direction = direction;
+        self.turn_directions.clear();
+        let shape = BodyShape::joining(direction, direction);
+        let mut position = head;
+        for i in 0..length {
+            if i > 0 {
+                position = position
+                    .step(direction.opposite())
+                    .expect("Snake does not fit on the board");
+            }
+            self.body.append(position);
+            board.set(position, Cell::Body(shape));
+        }
+        board.set(head, Cell::Head(direction));
+    }
+
+    /// Forget the snake's body and queued turns, leaving the board as it is
+    pub fn clear(&mut self) {
+        self.body.clear();
+        self.turn_directions.clear();
+    }
+
+    /// Check if the snake is on the board
+    pub fn is_on_board(&self) -> bool {
+        !self.body.is_empty()
+    }
+
+    /// Position of snake's head
+    pub fn head(&self) -> Position {
+        *self.body.peek_first()
+    }
+
+    /// Position of snake's tail
+    pub fn tail(&self) -> Position {
+        *self.body.peek_last()
+    }
+
+    /// Current direction the snake is moving in
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Number of cells occupied by the snake
+    pub fn length(&self) -> usize {
+        self.body.len()
+    }
+
+    /// Positions of the cells occupied by the snake, from head to tail
+    pub fn positions<'b>(&'b self) -> impl Iterator<Item = Position> + 'b {
+        (0..self.body.len()).map(move |i| *self.body.peek_ith(i))
+    }
+
+    /// Direction the snake moves in on the next tick, taking the first queued turn if any
+    fn next_direction(&self) -> Direction {
+        if self.turn_directions.is_empty() {
+            self.direction
+        } else {
+            *self.turn_directions.peek_first()
+        }
+    }
+
+    /// Position the head moves to on the next tick, or None if it would run into a solid edge
+    pub fn next_head(&self, board: &Board) -> Option<Position> {
+        board.neighbour(self.head(), self.next_direction())
+    }
+
+    /// Process snake's movement on the board per tick
+    pub fn tick(&mut self, board: &mut Board) -> Move {
+        // Take a single turn per tick, leaving the rest for upcoming ticks
+        let direction = self.next_direction();
+        if !self.turn_directions.is_empty() {
+            self.turn_directions.pop_first();
+        }
+
+        let head_position = self.head();
+        let new_head_position = match board.neighbour(head_position, direction) {
+            Some(position) => position,
+            // Stepping past a solid edge means hitting the boundary
+            None => return Move::Collided,
+        };
+        let result = match board.get(new_head_position) {
+            Cell::Empty => Move::Moved,
+            Cell::Food(kind) => Move::Ate(kind),
+            Cell::Wall | Cell::Head(_) | Cell::Body(_) => return Move::Collided,
+        };
+        self.body.prepend(new_head_position);
+        board.set(new_head_position, Cell::Head(direction));
+        board.set(
+            head_position,
+            Cell::Body(BodyShape::joining(self.direction, direction)),
+        );
+        self.direction = direction;
+        if result == Move::Moved {
+            let drop_position = *self.body.pop_last();
+            board.set(drop_position, Cell::Empty);
+        }
+        result
+    }
+
+    /// Take up to given number of cells off the tail, keeping at least `min_length` of them
+    pub fn shrink(&mut self, cells: usize, min_length: usize, board: &mut Board) {
+        for _ in 0..cells {
+            if self.body.len() <= min_length.max(1) {
+                break;
+            }
+            let drop_position = *self.body.pop_last();
+            board.set(drop_position, Cell::Empty);
+        }
+    }
+
+    /// Queue a direction to turn to on an upcoming tick. Return true if the turn was queued.
+    pub fn set_turn_direction(&mut self, turn_direction: Direction) -> bool {
+        // Compare with the direction snake will be moving in once earlier turns are taken
+        let last_direction = if self.turn_directions.is_empty() {
+            self.direction
+        } else {
+            *self.turn_directions.peek_last()
+        };
+        if turn_direction == last_direction || turn_direction == last_direction.opposite() {
+            // Already moving that way, or would turn back into its own body
+            return false;
+        }
+        if self.turn_directions.is_full() {
+            return false;
+        }
+        self.turn_directions.append(turn_direction);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_snake<'s>(buffer: &'s mut [Position], board: &mut Board) -> Snake<'s> {
+        let head = Position { row: 10, col: 10 };
+        Snake::new(buffer, head, Direction::Left, 3, board)
+    }
+
+    #[test]
+    fn quick_key_presses_turn_on_consecutive_ticks() {
+        let mut buffer = [Position::default(); 8];
+        let mut board = Board::default();
+        let mut snake = new_snake(&mut buffer, &mut board);
+        // U-turn within a single tick
+        snake.set_turn_direction(Direction::Up);
+        snake.set_turn_direction(Direction::Right);
+        snake.tick(&mut board);
+        assert_eq!(snake.direction(), Direction::Up);
+        assert_eq!(snake.head(), Position { row: 9, col: 10 });
+        snake.tick(&mut board);
+        assert_eq!(snake.direction(), Direction::Right);
+        assert_eq!(snake.head(), Position { row: 9, col: 11 });
+    }
+
+    #[test]
+    fn reversal_into_body_is_ignored() {
+        let mut buffer = [Position::default(); 8];
+        let mut board = Board::default();
+        let mut snake = new_snake(&mut buffer, &mut board);
+        assert!(!snake.set_turn_direction(Direction::Right));
+        assert_eq!(snake.tick(&mut board), Move::Moved);
+        assert_eq!(snake.direction(), Direction::Left);
+        // Reversal is checked against the last queued turn
+        assert!(snake.set_turn_direction(Direction::Up));
+        assert!(!snake.set_turn_direction(Direction::Down));
+        snake.tick(&mut board);
+        snake.tick(&mut board);
+        assert_eq!(snake.direction(), Direction::Up);
+    }
+
+    #[test]
+    fn shrinking_keeps_minimum_length() {
+        let mut buffer = [Position::default(); 8];
+        let mut board = Board::default();
+        let head = Position { row: 10, col: 10 };
+        let mut snake = Snake::new(&mut buffer, head, Direction::Left, 6, &mut board);
+        snake.shrink(2, 3, &mut board);
+        assert_eq!(snake.length(), 4);
+        assert_eq!(board.get(Position { row: 10, col: 14 }), Cell::Empty);
+        assert_eq!(
+            board.get(Position { row: 10, col: 13 }),
+            Cell::Body(BodyShape::Horizontal)
+        );
+        snake.shrink(5, 3, &mut board);
+        assert_eq!(snake.length(), 3);
+        assert_eq!(snake.head(), head);
+    }
+
+    #[test]
+    fn turn_queue_is_bounded() {
+        let mut buffer = [Position::default(); 8];
+        let mut board = Board::default();
+        let mut snake = new_snake(&mut buffer, &mut board);
+        let turns = [
+            Direction::Up,
+            Direction::Left,
+            Direction::Down,
+            Direction::Right,
+        ];
+        for turn in turns.iter() {
+            snake.set_turn_direction(*turn);
+        }
+        for turn in turns.iter().take(TURN_QUEUE_SIZE) {
+            snake.tick(&mut board);
+            assert_eq!(snake.direction(), *turn);
+        }
+        // Last key press did not fit in the queue
+        snake.tick(&mut board);
+        assert_eq!(snake.direction(), Direction::Down);
+    }
+}
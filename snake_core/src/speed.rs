@@ -0,0 +1,152 @@
+/* Speed levels deciding how often the snake moves
+//
+// The game is driven by a periodic timer. A speed level maps to the number of
+// timer ticks between two steps of the snake, and the level goes up as the
+// score grows.
+*/
+
+/// Frequency of the timer ticks the speed levels are designed for
+pub const TICKS_PER_SECOND: u32 = 100;
+/// Slowest speed level
+pub const MIN_LEVEL: u8 = 1;
+/// Fastest speed level
+pub const MAX_LEVEL: u8 = 9;
+/// Speed level used unless configured otherwise
+pub const DEFAULT_LEVEL: u8 = 3;
+// Points to score for going up by one speed level
+const POINTS_PER_LEVEL: u16 = 5;
+// Timer ticks between two steps of the snake, for each level from MIN_LEVEL to MAX_LEVEL
+const TICKS_PER_STEP: [u32; 9] = [20, 16, 13, 11, 9, 8, 7, 6, 5];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Speed {
+    /// Level the game started with
+    start_level: u8,
+    /// Level the game is currently played at
+    level: u8,
+    /// Timer ticks counted since the snake last moved
+    elapsed_ticks: u32,
+    /// Levels added on top of the current one for a while
+    boost_levels: u8,
+    /// Steps left until the boost wears off
+    boost_steps: u32,
+}
+
+impl Speed {
+    /// Create speed starting at given level, limited to the range of valid levels
+    pub fn new(start_level: u8) -> Self {
+        let start_level = clamp_level(start_level);
+        Speed {
+            start_level,
+            level: start_level,
+            elapsed_ticks: 0,
+            boost_levels: 0,
+            boost_steps: 0,
+        }
+    }
+
+    /// Get the current level
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    /// Get the level the snake is moving at, including a boost if one is active
+    pub fn effective_level(&self) -> u8 {
+        if self.boost_steps > 0 {
+            clamp_level(self.level + self.boost_levels)
+        } else {
+            self.level
+        }
+    }
+
+    /// Check if a boost is active
+    pub fn is_boosted(&self) -> bool {
+        self.boost_steps > 0
+    }
+
+    /// Move given number of levels faster for given number of steps, replacing an earlier boost
+    pub fn boost(&mut self, levels: u8, steps: u32) {
+        self.boost_levels = levels;
+        self.boost_steps = steps;
+    }
+
+    /// Count a step of the snake taken, wearing off the boost
+    pub fn step(&mut self) {
+        self.boost_steps = self.boost_steps.saturating_sub(1);
+    }
+
+    /// Number of timer ticks between two steps of the snake at current level, including a boost
+    pub fn ticks_per_step(&self) -> u32 {
+        TICKS_PER_STEP[(self.effective_level() - MIN_LEVEL) as usize]
+    }
+
+    /// Count one timer tick, and return true if the snake should take a step now
+    pub fn tick(&mut self) -> bool {
+        self.elapsed_ticks += 1;
+        if self.elapsed_ticks >= self.ticks_per_step() {
+            self.elapsed_ticks = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Go up a level for every `POINTS_PER_LEVEL` points scored
+    pub fn update(&mut self, score: u16) {
+        let gained_levels = (score / POINTS_PER_LEVEL).min(MAX_LEVEL as u16) as u8;
+        self.level = clamp_level(self.start_level + gained_levels);
+    }
+}
+
+/// Limit level to the range of valid levels
+fn clamp_level(level: u8) -> u8 {
+    level.max(MIN_LEVEL).min(MAX_LEVEL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_once_every_ticks_per_step() {
+        let mut speed = Speed::new(MIN_LEVEL);
+        let mut steps = 0;
+        for _ in 0..(TICKS_PER_STEP[0] * 3) {
+            if speed.tick() {
+                steps += 1;
+            }
+        }
+        assert_eq!(steps, 3);
+    }
+
+    #[test]
+    fn level_grows_with_score_up_to_max() {
+        let mut speed = Speed::new(2);
+        speed.update(POINTS_PER_LEVEL - 1);
+        assert_eq!(speed.level(), 2);
+        speed.update(POINTS_PER_LEVEL * 3);
+        assert_eq!(speed.level(), 5);
+        assert!(speed.ticks_per_step() < Speed::new(2).ticks_per_step());
+        speed.update(u16::MAX);
+        assert_eq!(speed.level(), MAX_LEVEL);
+    }
+
+    #[test]
+    fn boost_wears_off_after_its_steps() {
+        let mut speed = Speed::new(MAX_LEVEL - 1);
+        speed.boost(3, 2);
+        assert!(speed.is_boosted());
+        assert_eq!(speed.effective_level(), MAX_LEVEL);
+        assert_eq!(speed.level(), MAX_LEVEL - 1);
+        speed.step();
+        speed.step();
+        assert!(!speed.is_boosted());
+        assert_eq!(speed.effective_level(), MAX_LEVEL - 1);
+    }
+
+    #[test]
+    fn invalid_levels_are_clamped() {
+        assert_eq!(Speed::new(0).level(), MIN_LEVEL);
+        assert_eq!(Speed::new(42).level(), MAX_LEVEL);
+    }
+}
@@ -0,0 +1,273 @@
+/* Kernel heap backing the `alloc` crate
+//
+// The heap is a region reserved by the linker script right after the kernel
+// image, inside the identity mapped first GiB, so no page needs to be mapped
+// for it. Free memory is kept in a linked list sorted by address, and regions
+// touching each other are merged when freed so the heap does not fragment into
+// pieces too small to use.
+//
+// Based upon https://os.phil-opp.com/allocator-designs/
+// Copyright (c) 2019 Philipp Oppermann
+*/
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::{mem, ptr};
+use spin::Mutex;
+use x86_64::instructions::interrupts;
+
+#[global_allocator]
+static ALLOCATOR: Locked<LinkedListAllocator> = Locked::new(LinkedListAllocator::new());
+
+extern "C" {
+    // Bounds of the heap region, defined in linker script
+    static __heap_start: u8;
+    static __heap_end: u8;
+}
+
+/// Hand the heap region over to the allocator. Must be called once before anything is allocated.
+pub fn init() {
+    let heap_start = unsafe { &__heap_start as *const u8 as usize };
+    let heap_end = unsafe { &__heap_end as *const u8 as usize };
+    unsafe {
+        ALLOCATOR
+            .inner
+            .lock()
+            .init(heap_start, heap_end - heap_start)
+    };
+}
+
+/// Wrapper to implement `GlobalAlloc`, which takes `&self`, for an allocator behind a lock
+pub struct Locked<A> {
+    inner: Mutex<A>,
+}
+
+impl<A> Locked<A> {
+    pub const fn new(inner: A) -> Self {
+        Locked {
+            inner: Mutex::new(inner),
+        }
+    }
+}
+
+/// Free region of the heap, stored at the start of the region itself
+struct ListNode {
+    size: usize,
+    next: Option<&'static mut ListNode>,
+}
+
+impl ListNode {
+    fn start_addr(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    fn end_addr(&self) -> usize {
+        self.start_addr() + self.size
+    }
+}
+
+pub struct LinkedListAllocator {
+    /// Dummy node of size 0 pointing to the free region with the lowest address
+    head: ListNode,
+}
+
+impl LinkedListAllocator {
+    /// Create an allocator without any memory to hand out
+    pub const fn new() -> Self {
+        LinkedListAllocator {
+            head: ListNode {
+                size: 0,
+                next: None,
+            },
+        }
+    }
+
+    /// Give the allocator a region of memory, which must be unused and stay valid forever
+    pub unsafe fn init(&mut self, heap_start: usize, heap_size: usize) {
+        self.add_free_region(heap_start, heap_size);
+    }
+
+    /// Put a region back in the list, merging it with its neighbours if they touch
+    unsafe fn add_free_region(&mut self, addr: usize, size: usize) {
+        assert_eq!(align_up(addr, mem::align_of::<ListNode>()), addr);
+        assert!(size >= mem::size_of::<ListNode>());
+
+        // Find the last region starting before this one
+        let mut current = &mut self.head;
+        while current
+            .next
+            .as_ref()
+            .map_or(false, |next| next.start_addr() < addr)
+        {
+            current = current.next.as_mut().unwrap();
+        }
+
+        let mut size = size;
+        let mut next = current.next.take();
+        if let Some(following) = next.take() {
+            if addr + size == following.start_addr() {
+                size += following.size;
+                next = following.next.take();
+            } else {
+                next = Some(following);
+            }
+        }
+        // Head is not a region of the heap, so it never grows
+        if current.size > 0 && current.end_addr() == addr {
+            current.size += size;
+            current.next = next;
+        } else {
+            let node_ptr = addr as *mut ListNode;
+            node_ptr.write(ListNode { size, next });
+            current.next = Some(&mut *node_ptr);
+        }
+    }
+
+    /// Remove the first free region big enough for an allocation, and return it with the allocation's start address
+    fn find_region(&mut self, size: usize, align: usize) -> Option<(&'static mut ListNode, usize)> {
+        let mut current = &mut self.head;
+        while let Some(ref mut region) = current.next {
+            if let Some(alloc_start) = Self::alloc_from_region(region, size, align) {
+                let next = region.next.take();
+                let found = Some((current.next.take().unwrap(), alloc_start));
+                current.next = next;
+                return found;
+            } else {
+                current = current.next.as_mut().unwrap();
+            }
+        }
+        None
+    }
+
+    /// Start address of an allocation inside given region, if it fits. Whatever is left in front of and
+    /// behind the allocation has to be big enough to be put back in the list.
+    fn alloc_from_region(region: &ListNode, size: usize, align: usize) -> Option<usize> {
+        let node_size = mem::size_of::<ListNode>();
+        let mut alloc_start = align_up(region.start_addr(), align);
+        if alloc_start != region.start_addr() && alloc_start - region.start_addr() < node_size {
+            alloc_start = align_up(region.start_addr() + node_size, align);
+        }
+        let alloc_end = alloc_start.checked_add(size)?;
+        if alloc_end > region.end_addr() {
+            return None;
+        }
+        let excess_size = region.end_addr() - alloc_end;
+        if excess_size > 0 && excess_size < node_size {
+            return None;
+        }
+        Some(alloc_start)
+    }
+
+    /// Adjust layout so the allocated memory can hold a `ListNode` once freed
+    fn size_align(layout: Layout) -> (usize, usize) {
+        let align = layout.align().max(mem::align_of::<ListNode>());
+        let size = align_up(
+            layout.size().max(mem::size_of::<ListNode>()),
+            mem::align_of::<ListNode>(),
+        );
+        (size, align)
+    }
+}
+
+unsafe impl GlobalAlloc for Locked<LinkedListAllocator> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let (size, align) = LinkedListAllocator::size_align(layout);
+        // Disable interrupts to avoid deadlock
+        interrupts::without_interrupts(|| {
+            let mut allocator = self.inner.lock();
+            match allocator.find_region(size, align) {
+                Some((region, alloc_start)) => {
+                    let region_start = region.start_addr();
+                    let region_end = region.end_addr();
+                    let alloc_end = alloc_start + size;
+                    if alloc_start > region_start {
+                        allocator.add_free_region(region_start, alloc_start - region_start);
+                    }
+                    if region_end > alloc_end {
+                        allocator.add_free_region(alloc_end, region_end - alloc_end);
+                    }
+                    alloc_start as *mut u8
+                }
+                None => ptr::null_mut(),
+            }
+        })
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let (size, _) = LinkedListAllocator::size_align(layout);
+        // Disable interrupts to avoid deadlock
+        interrupts::without_interrupts(|| {
+            self.inner.lock().add_free_region(ptr as usize, size);
+        });
+    }
+}
+
+/// Round address up to given alignment, which must be a power of two
+fn align_up(addr: usize, align: usize) -> usize {
+    (addr + align - 1) & !(align - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+
+    /// Total size of free regions
+    fn free_size() -> usize {
+        interrupts::without_interrupts(|| {
+            let allocator = ALLOCATOR.inner.lock();
+            let mut size = 0;
+            let mut current = &allocator.head;
+            while let Some(ref region) = current.next {
+                size += region.size;
+                current = &**region;
+            }
+            size
+        })
+    }
+
+    #[test_case]
+    fn boxed_values_are_allocated() {
+        let first = Box::new(41);
+        let second = Box::new(13);
+        assert_eq!(*first, 41);
+        assert_eq!(*second, 13);
+        assert_ne!(&*first as *const i32, &*second as *const i32);
+    }
+
+    #[test_case]
+    fn growing_vector_keeps_its_elements() {
+        let mut vec = Vec::new();
+        for i in 0..1000 {
+            vec.push(i);
+        }
+        assert_eq!(vec.iter().sum::<u64>(), 999 * 1000 / 2);
+    }
+
+    #[test_case]
+    fn freed_memory_is_reused() {
+        let heap_size =
+            unsafe { &__heap_end as *const u8 as usize - &__heap_start as *const u8 as usize };
+        // Allocating the heap's size many times over only works if memory is given back
+        for i in 0..(heap_size / 64) {
+            let value = Box::new([i; 8]);
+            assert_eq!(value[7], i);
+        }
+    }
+
+    #[test_case]
+    fn neighbouring_regions_are_merged() {
+        let before = free_size();
+        let first: Vec<u8> = Vec::with_capacity(4096);
+        let second: Vec<u8> = Vec::with_capacity(4096);
+        let third: Vec<u8> = Vec::with_capacity(4096);
+        drop(second);
+        drop(first);
+        drop(third);
+        assert_eq!(free_size(), before);
+        // Only fits if the three regions became one again
+        let whole: Vec<u8> = Vec::with_capacity(before - 64);
+        drop(whole);
+        assert_eq!(free_size(), before);
+    }
+}
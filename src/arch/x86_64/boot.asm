@@ -21,6 +21,8 @@ bits 32
 start:
     ; use the stack reserved below
     mov esp, stack_top
+    ; keep the Multiboot2 information pointer, edi becomes the first argument of rust_main
+    mov edi, ebx
     
     ; check multiboot bootloader
     call check_multiboot_compliant_bootloader
@@ -211,8 +213,8 @@ p2_table:
 ; we don't need p1_table because we reserved 2MiB hugepages
 
 ; TODO: Introduce guard page. Absence of it causes Page tables to be overwritten, causing "interesting" issues
-; reserve space for stack
+; reserve space for stack, with room for the computer opponent's path searches run from timer interrupts
 stack_bottom:
-    resb 4096 * 4
+    resb 4096 * 16
 ; stack grows from higher address to lower address
 stack_top:
@@ -0,0 +1,244 @@
+/* ATA PIO driver for the master drive on the primary IDE channel
+//
+// Sectors are transferred one at a time by polling the status register, using
+// 28-bit LBA addressing. Interrupts of the drive are disabled, since nothing
+// waits for them.
+// Based upon https://wiki.osdev.org/ATA_PIO_Mode
+*/
+
+use lazy_static::lazy_static;
+use snake_core::record::SECTOR_SIZE;
+use spin::Mutex;
+use x86_64::instructions::port::Port;
+
+use crate::serial_println;
+
+const DATA_PORT: u16 = 0x1F0;
+const ERROR_PORT: u16 = 0x1F1;
+const SECTOR_COUNT_PORT: u16 = 0x1F2;
+const LBA_LOW_PORT: u16 = 0x1F3;
+const LBA_MID_PORT: u16 = 0x1F4;
+const LBA_HIGH_PORT: u16 = 0x1F5;
+const DRIVE_PORT: u16 = 0x1F6;
+const STATUS_COMMAND_PORT: u16 = 0x1F7;
+const ALTERNATE_STATUS_CONTROL_PORT: u16 = 0x3F6;
+
+const IDENTIFY_COMMAND: u8 = 0xEC;
+const READ_SECTORS_COMMAND: u8 = 0x20;
+const WRITE_SECTORS_COMMAND: u8 = 0x30;
+const CACHE_FLUSH_COMMAND: u8 = 0xE7;
+
+const STATUS_ERROR: u8 = 0x01;
+const STATUS_DATA_REQUEST: u8 = 0x08;
+const STATUS_DRIVE_FAULT: u8 = 0x20;
+const STATUS_BUSY: u8 = 0x80;
+
+// Select master drive for IDENTIFY, and master drive with LBA addressing for transfers
+const SELECT_MASTER: u8 = 0xA0;
+const SELECT_MASTER_LBA: u8 = 0xE0;
+// Control register bit stopping the drive from raising interrupts
+const CONTROL_INTERRUPTS_DISABLED: u8 = 0x02;
+// Status reads to give up after while waiting for the drive
+const POLL_LIMIT: usize = 1_000_000;
+
+lazy_static! {
+    /// Master drive on the primary IDE channel, if one is attached
+    pub static ref PRIMARY_DRIVE: Mutex<Option<Drive>> = Mutex::new(match Drive::identify() {
+        Ok(drive) => {
+            serial_println!("ATA drive found with {} sectors", drive.sectors());
+            Some(drive)
+        }
+        Err(error) => {
+            serial_println!("No ATA drive: {:?}", error);
+            None
+        }
+    });
+}
+
+/// Reasons a transfer with the drive can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AtaError {
+    /// Nothing answering as an ATA drive on the channel
+    NoDrive,
+    /// Drive did not get ready in time
+    Timeout,
+    /// Drive reported a fault
+    DriveFault,
+    /// Drive reported an error, with the content of its error register
+    Error(u8),
+    /// Sector lies past the end of the drive
+    OutOfRange,
+    /// Drive still asked for data once a whole sector was sent
+    DataLeftOver,
+}
+
+pub struct Drive {
+    /// Number of sectors addressable with 28-bit LBA
+    sectors: u32,
+}
+
+impl Drive {
+    /// Look for a drive and ask for its size
+    pub fn identify() -> Result<Drive, AtaError> {
+        let mut control_port = Port::new(ALTERNATE_STATUS_CONTROL_PORT);
+        let mut drive_port = Port::new(DRIVE_PORT);
+        let mut status_command_port: Port<u8> = Port::new(STATUS_COMMAND_PORT);
+        unsafe {
+            control_port.write(CONTROL_INTERRUPTS_DISABLED);
+            // No controller drives the bus, so it reads as all ones
+            if status_command_port.read() == 0xFF {
+                return Err(AtaError::NoDrive);
+            }
+            drive_port.write(SELECT_MASTER);
+        }
+        delay();
+        set_address(0, 0);
+        unsafe { status_command_port.write(IDENTIFY_COMMAND) };
+        if unsafe { status_command_port.read() } == 0 {
+            return Err(AtaError::NoDrive);
+        }
+        wait_while_busy()?;
+        // ATAPI and SATA devices put their signature here instead of answering
+        let mut lba_mid_port: Port<u8> = Port::new(LBA_MID_PORT);
+        let mut lba_high_port: Port<u8> = Port::new(LBA_HIGH_PORT);
+        if unsafe { lba_mid_port.read() != 0 || lba_high_port.read() != 0 } {
+            return Err(AtaError::NoDrive);
+        }
+        wait_for_data()?;
+        let mut data_port: Port<u16> = Port::new(DATA_PORT);
+        let mut identity = [0u16; SECTOR_SIZE / 2];
+        for word in identity.iter_mut() {
+            *word = unsafe { data_port.read() };
+        }
+        // Words 60 and 61 hold the number of LBA28 sectors, low word first
+        let sectors = ((identity[61] as u32) << 16) | identity[60] as u32;
+        if sectors == 0 {
+            return Err(AtaError::NoDrive);
+        }
+        Ok(Drive { sectors })
+    }
+
+    /// Number of sectors on the drive
+    pub fn sectors(&self) -> u32 {
+        self.sectors
+    }
+
+    /// Read a sector at given LBA into buffer
+    pub fn read_sector(
+        &mut self,
+        lba: u32,
+        buffer: &mut [u8; SECTOR_SIZE],
+    ) -> Result<(), AtaError> {
+        self.start_transfer(lba, READ_SECTORS_COMMAND)?;
+        wait_for_data()?;
+        let mut data_port: Port<u16> = Port::new(DATA_PORT);
+        for bytes in buffer.chunks_mut(2) {
+            let word = unsafe { data_port.read() };
+            bytes[0] = word as u8;
+            bytes[1] = (word >> 8) as u8;
+        }
+        delay();
+        Ok(())
+    }
+
+    /// Write buffer to a sector at given LBA, and wait until it reaches the disk
+    pub fn write_sector(&mut self, lba: u32, buffer: &[u8; SECTOR_SIZE]) -> Result<(), AtaError> {
+        self.start_transfer(lba, WRITE_SECTORS_COMMAND)?;
+        wait_for_data()?;
+        let mut data_port: Port<u16> = Port::new(DATA_PORT);
+        for bytes in buffer.chunks(2) {
+            unsafe { data_port.write(bytes[0] as u16 | ((bytes[1] as u16) << 8)) };
+        }
+        delay();
+        // A drive still busy with the sector would ignore the flush, or take it as a bad command
+        wait_until_done()?;
+        let mut status_command_port: Port<u8> = Port::new(STATUS_COMMAND_PORT);
+        unsafe { status_command_port.write(CACHE_FLUSH_COMMAND) };
+        delay();
+        wait_until_done()
+    }
+
+    /// Select a single sector at given LBA, and issue command to transfer it
+    fn start_transfer(&mut self, lba: u32, command: u8) -> Result<(), AtaError> {
+        if lba >= self.sectors {
+            return Err(AtaError::OutOfRange);
+        }
+        wait_while_busy()?;
+        let mut drive_port = Port::new(DRIVE_PORT);
+        unsafe { drive_port.write(SELECT_MASTER_LBA | ((lba >> 24) & 0x0F) as u8) };
+        delay();
+        set_address(lba, 1);
+        let mut status_command_port = Port::new(STATUS_COMMAND_PORT);
+        unsafe { status_command_port.write(command) };
+        delay();
+        Ok(())
+    }
+}
+
+/// Write low 24 bits of LBA and number of sectors to transfer
+fn set_address(lba: u32, sector_count: u8) {
+    let mut sector_count_port = Port::new(SECTOR_COUNT_PORT);
+    let mut lba_low_port = Port::new(LBA_LOW_PORT);
+    let mut lba_mid_port = Port::new(LBA_MID_PORT);
+    let mut lba_high_port = Port::new(LBA_HIGH_PORT);
+    unsafe {
+        sector_count_port.write(sector_count);
+        lba_low_port.write(lba as u8);
+        lba_mid_port.write((lba >> 8) as u8);
+        lba_high_port.write((lba >> 16) as u8);
+    }
+}
+
+/// Give the drive 400ns to put status of a new command in place, by reading alternate status four times
+fn delay() {
+    let mut alternate_status_port: Port<u8> = Port::new(ALTERNATE_STATUS_CONTROL_PORT);
+    for _ in 0..4 {
+        unsafe { alternate_status_port.read() };
+    }
+}
+
+/// Read status register, turning error and fault bits into an error
+fn read_status() -> Result<u8, AtaError> {
+    let mut status_command_port: Port<u8> = Port::new(STATUS_COMMAND_PORT);
+    let status = unsafe { status_command_port.read() };
+    if status & STATUS_BUSY == 0 {
+        if status & STATUS_ERROR != 0 {
+            let mut error_port: Port<u8> = Port::new(ERROR_PORT);
+            return Err(AtaError::Error(unsafe { error_port.read() }));
+        }
+        if status & STATUS_DRIVE_FAULT != 0 {
+            return Err(AtaError::DriveFault);
+        }
+    }
+    Ok(status)
+}
+
+/// Wait until the drive is done with the last command
+fn wait_while_busy() -> Result<(), AtaError> {
+    for _ in 0..POLL_LIMIT {
+        if read_status()? & STATUS_BUSY == 0 {
+            return Ok(());
+        }
+    }
+    Err(AtaError::Timeout)
+}
+
+/// Wait until the drive is done with the last command, and check it took all data and reported no error
+fn wait_until_done() -> Result<(), AtaError> {
+    wait_while_busy()?;
+    if read_status()? & STATUS_DATA_REQUEST != 0 {
+        return Err(AtaError::DataLeftOver);
+    }
+    Ok(())
+}
+
+/// Wait until the drive is ready to transfer a sector
+fn wait_for_data() -> Result<(), AtaError> {
+    for _ in 0..POLL_LIMIT {
+        let status = read_status()?;
+        if status & STATUS_BUSY == 0 && status & STATUS_DATA_REQUEST != 0 {
+            return Ok(());
+        }
+    }
+    Err(AtaError::Timeout)
+}
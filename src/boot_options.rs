@@ -0,0 +1,44 @@
+/* Game options given on the kernel command line
+//
+// GRUB passes whatever follows the kernel on its `multiboot2` line, so
+// separate menu entries can start differently configured games without
+// rebuilding. See `snake_core::options` for the options understood.
+*/
+
+use lazy_static::lazy_static;
+use snake_core::options::Options;
+use spin::Mutex;
+
+use crate::multiboot::BootInformation;
+use crate::serial;
+use crate::serial_println;
+
+lazy_static! {
+    /// Options read from the command line, defaults until `init` is called
+    pub static ref BOOT_OPTIONS: Mutex<Options> = Mutex::new(Options::default());
+}
+
+/// Read options from the command line. Must be called before the game is started.
+pub fn init(boot_information: &BootInformation) {
+    let mut options = Options::default();
+    if let Some(command_line) = boot_information.command_line() {
+        for word in command_line.split_whitespace() {
+            if let Err(error) = options.apply(word) {
+                serial_println!("Ignoring boot option {}: {:?}", word, error);
+            }
+        }
+    }
+    // Last thing logged before the serial port may be switched off
+    serial_println!(
+        "Boot options: speed {}, level {}, {:?} edges, walls {}, {} players, opponent {:?}, seed {:?}",
+        options.config.start_speed,
+        options.config.start_level + 1,
+        options.config.edges,
+        if options.config.walls { "on" } else { "off" },
+        options.config.player_count(),
+        options.config.opponent,
+        options.seed
+    );
+    serial::set_enabled(options.serial);
+    *BOOT_OPTIONS.lock() = options;
+}
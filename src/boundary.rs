@@ -1,6 +1,11 @@
-/* Boundary element for the game */
+/* Boundary element for the game
+//
+// A solid line is drawn around the board when its edges are walls, and a dotted
+// one when the snake can pass through them.
+*/
 
 use lazy_static::lazy_static;
+use snake_core::board::Edges;
 use spin::Mutex;
 use x86_64::instructions::interrupts;
 
@@ -42,9 +47,18 @@ lazy_static! {
         ascii_character: 179,
         color_code: ColorCode::new(Color::White, Color::Black),
     };
+
+    /// Character for every part of boundaries the snake can pass through
+    static ref DOTTED_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 250,
+        color_code: ColorCode::new(Color::DarkGray, Color::Black),
+    };
 }
 
-pub struct Boundary {}
+pub struct Boundary {
+    /// Behaviour of the board's edges the boundary surrounds
+    pub edges: Edges,
+}
 
 // Leave one row empty for score
 const FIRST_ROW: usize = 1;
@@ -55,26 +69,37 @@ const LAST_COL: usize = BUFFER_WIDTH - 1;
 impl Boundary {
     /// Draw boundary element on screen
     pub fn draw(&self, screen: &Mutex<Writer>) {
+        // Passable edges use the same dotted character all around
+        let character = |solid: ScreenChar| match self.edges {
+            Edges::Solid => solid,
+            Edges::Wrap => *DOTTED_CHARACTER,
+        };
+        let tl_corner = character(*TL_CORNER_CHARACTER);
+        let tr_corner = character(*TR_CORNER_CHARACTER);
+        let bl_corner = character(*BL_CORNER_CHARACTER);
+        let br_corner = character(*BR_CORNER_CHARACTER);
+        let horizontal = character(*HORIZONTAL_CHARACTER);
+        let vertical = character(*VERTICAL_CHARACTER);
         // Disable interrupts to avoid deadlock
         interrupts::without_interrupts(|| {
             let mut writer = screen.lock();
             // Draw top-left corner
-            writer.write_character_at(*TL_CORNER_CHARACTER, FIRST_ROW, FIRST_COL);
+            writer.write_character_at(tl_corner, FIRST_ROW, FIRST_COL);
             // Draw top-right corner
-            writer.write_character_at(*TR_CORNER_CHARACTER, FIRST_ROW, LAST_COL);
+            writer.write_character_at(tr_corner, FIRST_ROW, LAST_COL);
             // Draw bottom-left corner
-            writer.write_character_at(*BL_CORNER_CHARACTER, LAST_ROW, FIRST_COL);
+            writer.write_character_at(bl_corner, LAST_ROW, FIRST_COL);
             // Draw bottom-right corner
-            writer.write_character_at(*BR_CORNER_CHARACTER, LAST_ROW, LAST_COL);
+            writer.write_character_at(br_corner, LAST_ROW, LAST_COL);
             // Draw first and last row
             for i in (FIRST_COL + 1)..LAST_COL {
-                writer.write_character_at(*HORIZONTAL_CHARACTER, FIRST_ROW, i);
-                writer.write_character_at(*HORIZONTAL_CHARACTER, LAST_ROW, i);
+                writer.write_character_at(horizontal, FIRST_ROW, i);
+                writer.write_character_at(horizontal, LAST_ROW, i);
             }
             // Draw first and last columns
             for i in (FIRST_ROW + 1)..LAST_ROW {
-                writer.write_character_at(*VERTICAL_CHARACTER, i, FIRST_COL);
-                writer.write_character_at(*VERTICAL_CHARACTER, i, LAST_COL);
+                writer.write_character_at(vertical, i, FIRST_COL);
+                writer.write_character_at(vertical, i, LAST_COL);
             }
         });
     }
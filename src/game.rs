@@ -0,0 +1,939 @@
+/* Module holding the game instance driven by interrupts
+//
+// The game starts on a title screen, where the timer does not move it on, and
+// a menu leads to playing, choosing options and other pages. When nobody
+// presses a key there for a while, a demo game starts, with the computer
+// pressing keys for the player until any key is pressed.
+//
+// The replay of the last game played from the keyboard is kept on disk, and
+// can be watched again or exported over the serial port to go along with a
+// bug report.
+//
+// Keys are looked up in a table of bindings, which the controls page changes
+// and which is kept on disk too. Enter, Escape and Backspace are left out of
+// it, so that menus and initials can always be used. Keys bound to pausing,
+// restarting or quitting come before keys choosing options of the next game.
+// Turns come after them while those options can be chosen, since snakes do not
+// move then, and turns only move through the title screen's menu.
+*/
+
+use alloc::boxed::Box;
+use core::sync::atomic::{AtomicBool, Ordering};
+use lazy_static::lazy_static;
+use snake_core::autopilot::{self, Difficulty};
+use snake_core::bindings::{self, Action, Bindings, Key, ACTIONS, ACTION_COUNT};
+use snake_core::board::{Edges, Position};
+use snake_core::game::{Events, Game, State, MAX_PLAYERS};
+use snake_core::high_scores::{HighScores, ENCODED_SIZE};
+use snake_core::options::Seed;
+use snake_core::record::{self, RecordKind};
+use snake_core::replay::{self, Replay};
+use snake_core::snake::{Direction, MAX_SNAKE_SIZE};
+use snake_core::speed;
+use spin::Mutex;
+use x86_64::instructions::interrupts;
+
+use crate::boot_options::BOOT_OPTIONS;
+use crate::hardware_random;
+use crate::menu::Menu;
+use crate::nvram;
+use crate::renderer;
+use crate::serial_print;
+use crate::serial_println;
+use crate::storage;
+use crate::system_time;
+use crate::vga_buffer::Writer;
+
+// Layout version of high scores saved on disk
+const HIGH_SCORES_VERSION: u8 = 1;
+// Layout version of settings saved on disk, which hold key bindings
+const SETTINGS_VERSION: u8 = 1;
+// Timer ticks without a key press on the title screen before a demo game starts
+const DEMO_DELAY_TICKS: u32 = 15 * speed::TICKS_PER_SECOND;
+// How well the computer plays demo games
+const DEMO_DIFFICULTY: Difficulty = Difficulty::Survival;
+// Timer ticks counted for every one while fast-forwarding a replay
+const FAST_FORWARD_TICKS: usize = 4;
+// Bytes of a replay exported on each line
+const EXPORT_LINE_BYTES: usize = 32;
+
+/// Items of the title screen's menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitleItem {
+    /// Start a game
+    Start,
+    /// Number of players, and the computer opponent if any
+    Mode,
+    /// Speed level games start at
+    Speed,
+    /// Page listing high scores
+    HighScores,
+    /// Page binding keys
+    Controls,
+    Reboot,
+}
+
+/// Items of the title screen's menu, from top to bottom
+pub const TITLE_ITEMS: [TitleItem; 6] = [
+    TitleItem::Start,
+    TitleItem::Mode,
+    TitleItem::Speed,
+    TitleItem::HighScores,
+    TitleItem::Controls,
+    TitleItem::Reboot,
+];
+
+/// Items of the controls page's menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlsItem {
+    /// Preset of bindings
+    Preset,
+    /// Key bound to an action
+    Binding(Action),
+    /// Go back to the title screen
+    Back,
+}
+
+/// Number of items in the controls page's menu
+pub const CONTROLS_ITEMS: usize = ACTION_COUNT + 2;
+
+/// Get the item of the controls page's menu at given index, from top to bottom
+pub fn controls_item(index: usize) -> ControlsItem {
+    match index {
+        0 => ControlsItem::Preset,
+        _ if index <= ACTION_COUNT => ControlsItem::Binding(ACTIONS[index - 1]),
+        _ => ControlsItem::Back,
+    }
+}
+
+/// What the screen shows, deciding what timer ticks and keys do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Screen {
+    /// Title screen with its menu, counting timer ticks since the last key press
+    Title { idle_ticks: u32 },
+    /// Page listing high scores, left with any key
+    HighScores,
+    /// Page binding keys, waiting for the key to bind to the selected action if rebinding
+    Controls { rebinding: bool },
+    /// Game played from the keyboard
+    Play,
+    /// Game played by the computer while nobody is at the keyboard
+    Demo,
+    /// Replay played back, at normal speed or fast-forwarding
+    Replay { fast_forward: bool },
+}
+
+lazy_static! {
+    /// What the screen currently shows
+    static ref SCREEN: Mutex<Screen> = Mutex::new(Screen::Title { idle_ticks: 0 });
+    /// Selection in the title screen's menu
+    static ref TITLE_MENU: Mutex<Menu> = Mutex::new(Menu::new(TITLE_ITEMS.len()));
+    /// Selection in the controls page's menu
+    static ref CONTROLS_MENU: Mutex<Menu> = Mutex::new(Menu::new(CONTROLS_ITEMS));
+}
+
+lazy_static! {
+    /// Keys bound to actions
+    static ref BINDINGS: Mutex<Bindings> = Mutex::new(read_bindings());
+}
+
+// Set when high scores, key bindings or the last replay changed and are not saved yet. Saving takes
+// a few milliseconds a sector, and is left to the idle loop rather than done in interrupt handlers.
+static HIGH_SCORES_UNSAVED: AtomicBool = AtomicBool::new(false);
+static BINDINGS_UNSAVED: AtomicBool = AtomicBool::new(false);
+static REPLAY_UNSAVED: AtomicBool = AtomicBool::new(false);
+// Set when the last replay was asked to be exported, which takes long over the serial port as well
+static EXPORT_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    /// Replay of the last game played from the keyboard, if any
+    static ref LAST_REPLAY: Mutex<Option<Replay>> = Mutex::new(read_replay());
+}
+
+lazy_static! {
+    /// Instance of the game being played
+    pub static ref GAME: Mutex<Game<'static>> = {
+        // Positions of snakes' bodies stay allocated for as long as the kernel runs
+        let body_buffer = Box::leak(
+            vec![Position::default(); MAX_SNAKE_SIZE * MAX_PLAYERS].into_boxed_slice(),
+        );
+        let seed = next_seed();
+        serial_println!("Starting game with seed {}", seed);
+        let mut game = Game::new(seed, BOOT_OPTIONS.lock().config, body_buffer);
+        game.set_high_scores(read_high_scores());
+        Mutex::new(game)
+    };
+}
+
+/// Read key bindings and the last replay from disk, so that it is not left to the interrupt handler
+/// touching them first. High scores are read along with the game, when it is first drawn.
+pub fn load() {
+    lazy_static::initialize(&BINDINGS);
+    lazy_static::initialize(&LAST_REPLAY);
+}
+
+/// Seed for food placement of the next game, as chosen by boot options
+fn next_seed() -> u64 {
+    match BOOT_OPTIONS.lock().seed {
+        Seed::Random => {
+            hardware_random::get_seed().unwrap_or_else(system_time::get_system_time_seed)
+        }
+        Seed::Fixed(seed) => seed,
+        Seed::Daily => system_time::get_daily_seed(),
+    }
+}
+
+/// Get what the screen currently shows
+fn current_screen() -> Screen {
+    *SCREEN.lock()
+}
+
+/// Check if the title screen is shown
+fn is_title() -> bool {
+    match current_screen() {
+        Screen::Title { .. } => true,
+        Screen::HighScores
+        | Screen::Controls { .. }
+        | Screen::Play
+        | Screen::Demo
+        | Screen::Replay { .. } => false,
+    }
+}
+
+/// Check if options of the next game can be chosen, which they can on the title screen and once a game is over
+fn can_choose_options(game: &Game) -> bool {
+    game.is_over() || is_title()
+}
+
+/// Draw the game on screen, under the title screen or a page if either is shown, or a caption of demo games and replays
+pub fn draw(screen: &Mutex<Writer>) {
+    redraw(&GAME.lock(), screen);
+}
+
+/// Draw given game on screen, under the title screen or a page if either is shown, or a caption of demo games and replays
+fn redraw(game: &Game, screen: &Mutex<Writer>) {
+    match current_screen() {
+        Screen::Title { .. } => {
+            renderer::draw_playfield(game, screen);
+            renderer::draw_title(game, &TITLE_MENU.lock(), screen);
+        }
+        Screen::HighScores => {
+            renderer::draw_playfield(game, screen);
+            renderer::draw_high_scores(game, screen);
+        }
+        Screen::Controls { rebinding } => {
+            renderer::draw_playfield(game, screen);
+            renderer::draw_controls(&BINDINGS.lock(), &CONTROLS_MENU.lock(), rebinding, screen);
+        }
+        Screen::Play => renderer::draw(game, screen),
+        Screen::Demo => {
+            renderer::draw_playfield(game, screen);
+            renderer::draw_demo_caption(screen);
+        }
+        Screen::Replay { fast_forward } => {
+            renderer::draw(game, screen);
+            if game.state() == State::Playing {
+                renderer::draw_replay_caption(fast_forward, screen);
+            }
+        }
+    }
+}
+
+/// Count a timer tick, and draw the game on screen whenever a snake takes a step.
+/// Start a demo game once the title screen was left alone for long enough.
+pub fn tick(screen: &Mutex<Writer>) {
+    match current_screen() {
+        Screen::Title { idle_ticks } if idle_ticks + 1 >= DEMO_DELAY_TICKS => start_demo(screen),
+        Screen::Title { idle_ticks } => {
+            *SCREEN.lock() = Screen::Title {
+                idle_ticks: idle_ticks + 1,
+            }
+        }
+        Screen::HighScores | Screen::Controls { .. } => (),
+        Screen::Play => {
+            let mut game = GAME.lock();
+            if let Some(events) = game.tick() {
+                log_events(&game, events);
+                if game.is_over() {
+                    keep_replay(game.replay());
+                }
+                redraw(&game, screen);
+            }
+        }
+        Screen::Replay { fast_forward } => {
+            let mut game = GAME.lock();
+            let ticks = if fast_forward { FAST_FORWARD_TICKS } else { 1 };
+            let stepped = (0..ticks).filter(|_| game.tick().is_some()).count() > 0;
+            if stepped {
+                if game.is_over() {
+                    serial_println!("Replay over. Score: {}", game.score().get_score());
+                }
+                redraw(&game, screen);
+            }
+        }
+        Screen::Demo => {
+            steer_demo_snakes();
+            let mut game = GAME.lock();
+            if game.tick().is_some() {
+                if game.is_over() {
+                    serial_println!("Demo game over. Score: {}", game.score().get_score());
+                    show_title(&game, screen);
+                } else {
+                    redraw(&game, screen);
+                }
+            }
+        }
+    }
+}
+
+/// Start a game played by the computer
+fn start_demo(screen: &Mutex<Writer>) {
+    let mut game = GAME.lock();
+    game.restart(next_seed());
+    *SCREEN.lock() = Screen::Demo;
+    serial_println!("Demo game started with seed {}", game.seed());
+    redraw(&game, screen);
+}
+
+/// Go back to the title screen, leaving the board of the last game under it
+fn show_title(game: &Game, screen: &Mutex<Writer>) {
+    *SCREEN.lock() = Screen::Title { idle_ticks: 0 };
+    redraw(game, screen);
+}
+
+/// Let the computer choose turns of the snakes in a demo game, pressing keys as a player would
+fn steer_demo_snakes() {
+    let mut turns = [None; MAX_PLAYERS];
+    {
+        let game = GAME.lock();
+        for (player, turn) in turns.iter_mut().enumerate().take(game.players()) {
+            // Computer opponent steers itself
+            if !game.is_computer(player) {
+                *turn = autopilot::choose_direction(
+                    game.board(),
+                    game.snake_of(player),
+                    DEMO_DIFFICULTY,
+                );
+            }
+        }
+    }
+    for (player, turn) in turns.iter().enumerate() {
+        if let Some(turn_direction) = turn {
+            set_turn_direction(player, *turn_direction);
+        }
+    }
+}
+
+/// Note a key press, which keeps a demo game from starting, ends one being played, or leaves the high scores page.
+/// Return true if the key ended a demo game or left the page, and should do nothing else.
+pub fn note_key_press(screen: &Mutex<Writer>) -> bool {
+    match current_screen() {
+        Screen::Title { .. } => {
+            *SCREEN.lock() = Screen::Title { idle_ticks: 0 };
+            false
+        }
+        Screen::HighScores => {
+            show_title(&GAME.lock(), screen);
+            true
+        }
+        Screen::Controls { .. } | Screen::Play | Screen::Replay { .. } => false,
+        Screen::Demo => {
+            serial_println!("Demo game ended");
+            show_title(&GAME.lock(), screen);
+            true
+        }
+    }
+}
+
+/// Log what happened during a step of the game to serial port
+fn log_events(game: &Game, events: Events) {
+    let score = game.score().get_score();
+    let length = game.snake().length();
+    for player in 0..game.players() {
+        if let Some(kind) = events.ate_food[player] {
+            serial_println!(
+                "{:?} food eaten by player {}. Score: {}, length: {}, speed: {}",
+                kind,
+                player + 1,
+                game.score_of(player).get_score(),
+                game.snake_of(player).length(),
+                game.speed().effective_level()
+            );
+        }
+        if events.collided[player] && game.players() > 1 {
+            serial_println!("Player {} crashed", player + 1);
+        }
+    }
+    if events.special_food_expired {
+        serial_println!("Special food disappeared");
+    }
+    if events.level_up {
+        serial_println!(
+            "Level {} started: {}",
+            game.level_number(),
+            game.level().name
+        );
+    }
+    if events.collided.iter().any(|collided| *collided) {
+        if game.players() > 1 {
+            log_winner(game);
+        } else {
+            serial_println!("Game over. Score: {}, length: {}", score, length);
+        }
+    }
+    if events.won {
+        if game.players() > 1 {
+            log_winner(game);
+        } else {
+            serial_println!("Game won. Score: {}, length: {}", score, length);
+        }
+    }
+    if game.initials().is_some() {
+        serial_println!("New high score: {}", score);
+    }
+}
+
+/// Log the outcome of a game played by more than one player
+fn log_winner(game: &Game) {
+    match game.winner() {
+        Some(player) => serial_println!("Player {} wins", player + 1),
+        None => serial_println!("Draw"),
+    }
+    for player in 0..game.players() {
+        serial_println!(
+            "Player {} score: {}, length: {}",
+            player + 1,
+            game.score_of(player).get_score(),
+            game.snake_of(player).length()
+        );
+    }
+}
+
+/// Handle a key pressed anywhere but while entering initials: do what it is bound to, or choose an option
+/// of the next game, or bind it to an action on the controls page
+pub fn press_key(key: Key, screen: &Mutex<Writer>) {
+    if let Screen::Controls { rebinding } = current_screen() {
+        controls_key(key, rebinding, screen);
+        return;
+    }
+    let action = BINDINGS.lock().action(key);
+    let options_first = match action {
+        Some(Action::Turn(..)) => can_choose_options(&GAME.lock()),
+        Some(Action::Pause) | Some(Action::Restart) | Some(Action::Quit) => false,
+        None => true,
+    };
+    if options_first && option_key(key, screen) {
+        return;
+    }
+    match action {
+        Some(Action::Turn(player, direction)) => direction_key(player, direction, screen),
+        Some(Action::Pause) => toggle_pause(screen),
+        Some(Action::Restart) => confirm(screen),
+        Some(Action::Quit) => quit(screen),
+        None => match key {
+            Key::ENTER => confirm(screen),
+            Key::ESCAPE => toggle_pause(screen),
+            // Arrow keys always move through the title screen's menu
+            Key::Up if is_title() => direction_key(0, Direction::Up, screen),
+            Key::Down if is_title() => direction_key(0, Direction::Down, screen),
+            Key::Left if is_title() => direction_key(0, Direction::Left, screen),
+            Key::Right if is_title() => direction_key(0, Direction::Right, screen),
+            _ => (),
+        },
+    }
+}
+
+/// Handle a key choosing an option of the next game, or watching or exporting the last one.
+/// Return false if it is none of those keys.
+fn option_key(key: Key, screen: &Mutex<Writer>) -> bool {
+    match key {
+        Key::Char(digit @ b'1'..=b'9') => set_start_speed(digit - b'0', screen),
+        Key::Char(b'M') => toggle_edges(screen),
+        Key::Char(b'L') => cycle_start_level(screen),
+        Key::Char(b'T') => toggle_players(screen),
+        Key::Char(b'C') => cycle_opponent(screen),
+        Key::Char(b'R') => start_replay(screen),
+        Key::Char(b'F') => toggle_fast_forward(screen),
+        Key::Char(b'X') => EXPORT_REQUESTED.store(true, Ordering::SeqCst),
+        _ => return false,
+    }
+    true
+}
+
+/// Handle a key pressed on the controls page: move through its menu and change the preset, or bind the key
+/// to the selected action once Enter chose it. Escape goes back to the title screen, or cancels rebinding.
+fn controls_key(key: Key, rebinding: bool, screen: &Mutex<Writer>) {
+    let game = GAME.lock();
+    {
+        let mut menu = CONTROLS_MENU.lock();
+        let mut bindings = BINDINGS.lock();
+        let item = controls_item(menu.selected());
+        match (item, key) {
+            (ControlsItem::Binding(action), _) if rebinding => {
+                if key == Key::ESCAPE {
+                    serial_println!("Key of {} left unchanged", action.name());
+                } else if bindings.bind(action, key) {
+                    serial_println!("{} bound to {}", key, action.name());
+                    BINDINGS_UNSAVED.store(true, Ordering::SeqCst);
+                } else {
+                    serial_println!("{} is kept for menus and initials", key);
+                }
+                *SCREEN.lock() = Screen::Controls { rebinding: false };
+            }
+            (_, Key::Up) => menu.select_previous(),
+            (_, Key::Down) => menu.select_next(),
+            (ControlsItem::Preset, Key::Left)
+            | (ControlsItem::Preset, Key::Right)
+            | (ControlsItem::Preset, Key::ENTER) => {
+                // Changed bindings go back to the first preset
+                let preset = match bindings.matching_preset() {
+                    Some(preset) if key == Key::Left => preset.previous(),
+                    Some(preset) => preset.next(),
+                    None => bindings::PRESETS[0],
+                };
+                *bindings = Bindings::preset(preset);
+                serial_println!("{} preset chosen", preset.name());
+                BINDINGS_UNSAVED.store(true, Ordering::SeqCst);
+            }
+            (ControlsItem::Binding(_), Key::ENTER) => {
+                *SCREEN.lock() = Screen::Controls { rebinding: true };
+            }
+            (ControlsItem::Back, Key::ENTER) | (_, Key::ESCAPE) => {
+                *SCREEN.lock() = Screen::Title { idle_ticks: 0 };
+            }
+            _ => return,
+        }
+    }
+    redraw(&game, screen);
+}
+
+/// Get the key bound to given action
+pub fn bound_key(action: Action) -> Key {
+    BINDINGS.lock().key(action)
+}
+
+/// Read key bindings from disk if one is attached and holds them, or start from the default ones otherwise
+fn read_bindings() -> Bindings {
+    if !storage::is_available() {
+        return Bindings::default();
+    }
+    let mut bytes = [0; bindings::ENCODED_SIZE];
+    match storage::load(RecordKind::Settings, &mut bytes) {
+        Ok((SETTINGS_VERSION, bindings::ENCODED_SIZE)) => {
+            if let Some(bindings) = Bindings::decode(&bytes) {
+                return bindings;
+            }
+            serial_println!("Invalid key bindings on disk");
+        }
+        Ok((version, _)) => serial_println!("Unknown settings version {} on disk", version),
+        Err(error) => serial_println!("Key bindings not read from disk: {:?}", error),
+    }
+    Bindings::default()
+}
+
+/// Save key bindings to disk if they changed since they were last saved and a disk is attached
+pub fn save_bindings() {
+    if !BINDINGS_UNSAVED.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    // Disable interrupts to avoid deadlock
+    let bindings = interrupts::without_interrupts(|| *BINDINGS.lock());
+    if !storage::is_available() {
+        serial_println!("Key bindings kept until reboot only: no disk");
+        return;
+    }
+    match storage::save(RecordKind::Settings, SETTINGS_VERSION, &bindings.encode()) {
+        Ok(()) => serial_println!("Key bindings saved"),
+        Err(error) => serial_println!("Key bindings kept until reboot only: {:?}", error),
+    }
+}
+
+/// Act on Enter or the restart key: choose the selected item of the title screen's menu, or start a new game
+/// once the current one is over
+fn confirm(screen: &Mutex<Writer>) {
+    if !is_title() {
+        restart(screen);
+        return;
+    }
+    let item = TITLE_ITEMS[TITLE_MENU.lock().selected()];
+    match item {
+        TitleItem::Start => restart(screen),
+        TitleItem::Mode | TitleItem::Speed => {
+            let mut game = GAME.lock();
+            change_title_option(&mut game, item, true);
+            redraw(&game, screen);
+        }
+        TitleItem::HighScores => show_page(Screen::HighScores, screen),
+        TitleItem::Controls => show_page(Screen::Controls { rebinding: false }, screen),
+        TitleItem::Reboot => {
+            serial_println!("Rebooting");
+            crate::reboot();
+        }
+    }
+}
+
+/// Show a page of the title screen's menu
+fn show_page(page: Screen, screen: &Mutex<Writer>) {
+    *SCREEN.lock() = page;
+    redraw(&GAME.lock(), screen);
+}
+
+/// Handle a key pointing in given direction: move through the title screen's menu, or queue a turn
+/// of given player's snake
+fn direction_key(player: usize, direction: Direction, screen: &Mutex<Writer>) {
+    if !is_title() {
+        set_turn_direction(player, direction);
+        return;
+    }
+    let mut game = GAME.lock();
+    {
+        let mut menu = TITLE_MENU.lock();
+        match direction {
+            Direction::Up => menu.select_previous(),
+            Direction::Down => menu.select_next(),
+            Direction::Left | Direction::Right => change_title_option(
+                &mut game,
+                TITLE_ITEMS[menu.selected()],
+                direction == Direction::Right,
+            ),
+        }
+    }
+    redraw(&game, screen);
+}
+
+/// Choose the next or previous value of an option of the next game the title screen's menu holds
+fn change_title_option(game: &mut Game, item: TitleItem, forward: bool) {
+    let step = |value: usize, count: usize| {
+        if forward {
+            (value + 1) % count
+        } else {
+            (value + count - 1) % count
+        }
+    };
+    match item {
+        TitleItem::Mode => {
+            // One or two players, then each difficulty of the computer opponent
+            let config = game.config();
+            let modes = 2 + autopilot::DIFFICULTIES.len();
+            let mode = match config.opponent {
+                Some(difficulty) if config.player_count() > 1 => {
+                    2 + autopilot::DIFFICULTIES
+                        .iter()
+                        .position(|other| *other == difficulty)
+                        .unwrap_or(0)
+                }
+                _ => config.player_count() - 1,
+            };
+            let mode = step(mode, modes);
+            game.set_players(if mode == 0 { 1 } else { MAX_PLAYERS });
+            game.set_opponent(
+                mode.checked_sub(2)
+                    .map(|index| autopilot::DIFFICULTIES[index]),
+            );
+        }
+        TitleItem::Speed => {
+            let levels = (speed::MAX_LEVEL - speed::MIN_LEVEL + 1) as usize;
+            let level = step(
+                (game.config().start_speed - speed::MIN_LEVEL) as usize,
+                levels,
+            );
+            game.set_start_speed(speed::MIN_LEVEL + level as u8);
+        }
+        TitleItem::Start | TitleItem::HighScores | TitleItem::Controls | TitleItem::Reboot => (),
+    }
+}
+
+/// Start a new game from the title screen or once the current one is over, and draw it on screen
+fn restart(screen: &Mutex<Writer>) {
+    let mut game = GAME.lock();
+    if is_title() || (game.is_over() && game.initials().is_none()) {
+        game.restart(next_seed());
+        *SCREEN.lock() = Screen::Play;
+        serial_println!(
+            "New {} player game against {:?} at speed {} on level {} with {:?} edges and seed {}",
+            game.players(),
+            game.opponent(),
+            game.speed().level(),
+            game.level_number(),
+            game.board().edges(),
+            game.seed()
+        );
+        redraw(&game, screen);
+    }
+}
+
+/// Leave the game being played or replayed for the title screen, over the board as it was left
+fn quit(screen: &Mutex<Writer>) {
+    match current_screen() {
+        Screen::Play | Screen::Replay { .. } => (),
+        Screen::Title { .. } | Screen::HighScores | Screen::Controls { .. } | Screen::Demo => {
+            return
+        }
+    }
+    serial_println!("Game left for the title screen");
+    show_title(&GAME.lock(), screen);
+}
+
+/// Check if the game is waiting for player's initials after a high score
+pub fn is_entering_initials() -> bool {
+    current_screen() == Screen::Play && GAME.lock().initials().is_some()
+}
+
+/// Handle a key typed while entering initials. Enter saves the high score once all letters are typed,
+/// and Escape leaves without saving it.
+pub fn enter_initials_key(character: char, screen: &Mutex<Writer>) {
+    let mut game = GAME.lock();
+    match character {
+        '\n' => {
+            if game.submit_initials() {
+                HIGH_SCORES_UNSAVED.store(true, Ordering::SeqCst);
+            }
+        }
+        '\u{8}' => game.delete_initial(),
+        '\u{1b}' => {
+            game.skip_initials();
+            serial_println!("High score left unsaved");
+        }
+        letter => game.enter_initial(letter),
+    }
+    redraw(&game, screen);
+}
+
+/// Read high scores from disk if one is attached and holds them, or from CMOS otherwise
+fn read_high_scores() -> HighScores {
+    if !storage::is_available() {
+        return nvram::read_high_scores();
+    }
+    let mut bytes = [0; ENCODED_SIZE];
+    match storage::load(RecordKind::HighScores, &mut bytes) {
+        Ok((HIGH_SCORES_VERSION, ENCODED_SIZE)) => {
+            if let Some(high_scores) = HighScores::decode(&bytes) {
+                return high_scores;
+            }
+        }
+        Ok((version, _)) => serial_println!("Unknown high scores version {} on disk", version),
+        Err(error) => serial_println!("High scores not read from disk: {:?}", error),
+    }
+    nvram::read_high_scores()
+}
+
+/// Save high scores to CMOS, and to disk if one is attached, if they changed since they were last saved
+pub fn save_high_scores() {
+    if !HIGH_SCORES_UNSAVED.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    // Disable interrupts to avoid deadlock
+    let high_scores = interrupts::without_interrupts(|| *GAME.lock().high_scores());
+    nvram::write_high_scores(&high_scores);
+    if !storage::is_available() {
+        serial_println!("High scores saved to CMOS");
+        return;
+    }
+    match storage::save(
+        RecordKind::HighScores,
+        HIGH_SCORES_VERSION,
+        &high_scores.encode(),
+    ) {
+        Ok(()) => serial_println!("High scores saved"),
+        Err(error) => serial_println!("High scores saved to CMOS only: {:?}", error),
+    }
+}
+
+/// Keep the replay of a game which ended as the last one, to be saved by `save_replay`
+fn keep_replay(replay: &Replay) {
+    if !replay.is_complete() {
+        serial_println!("Replay ran out of room, and ends before the game did");
+    }
+    *LAST_REPLAY.lock() = Some(replay.clone());
+    REPLAY_UNSAVED.store(true, Ordering::SeqCst);
+}
+
+/// Save the last replay to disk if it changed since it was last saved and a disk is attached
+pub fn save_replay() {
+    if !REPLAY_UNSAVED.swap(false, Ordering::SeqCst) || !storage::is_available() {
+        return;
+    }
+    // Disable interrupts to avoid deadlock
+    let replay = interrupts::without_interrupts(|| LAST_REPLAY.lock().clone());
+    if let Some(replay) = replay {
+        match storage::save(RecordKind::Replay, replay::VERSION, replay.as_bytes()) {
+            Ok(()) => serial_println!("Replay saved"),
+            Err(error) => serial_println!("Replay kept in memory only: {:?}", error),
+        }
+    }
+}
+
+/// Read the replay of the last game from disk, if one is attached and holds it
+fn read_replay() -> Option<Replay> {
+    if !storage::is_available() {
+        return None;
+    }
+    let mut bytes = [0; replay::REPLAY_CAPACITY];
+    match storage::load(RecordKind::Replay, &mut bytes) {
+        Ok((replay::VERSION, length)) => Replay::decode(&bytes[..length]),
+        Ok((version, _)) => {
+            serial_println!("Unknown replay version {} on disk", version);
+            None
+        }
+        Err(error) => {
+            serial_println!("Replay not read from disk: {:?}", error);
+            None
+        }
+    }
+}
+
+/// Play the last game back from the title screen or once the current one is over, and draw it on screen
+fn start_replay(screen: &Mutex<Writer>) {
+    let mut game = GAME.lock();
+    if !is_title() && !(game.is_over() && game.initials().is_none()) {
+        return;
+    }
+    match LAST_REPLAY.lock().clone() {
+        Some(replay) => {
+            serial_println!(
+                "Replaying game with seed {}, {} bytes",
+                replay.seed(),
+                replay.as_bytes().len()
+            );
+            if !replay.is_complete() {
+                serial_println!("Replay ends before the game did, and may play out differently");
+            }
+            game.play_replay(replay);
+            *SCREEN.lock() = Screen::Replay {
+                fast_forward: false,
+            };
+            redraw(&game, screen);
+        }
+        None => serial_println!("No game to replay yet"),
+    }
+}
+
+/// Switch between normal speed and fast-forwarding while a replay is played back
+fn toggle_fast_forward(screen: &Mutex<Writer>) {
+    if let Screen::Replay { fast_forward } = current_screen() {
+        *SCREEN.lock() = Screen::Replay {
+            fast_forward: !fast_forward,
+        };
+        redraw(&GAME.lock(), screen);
+    }
+}
+
+/// Write the replay of the last game to serial port as hex lines, to be attached to bug reports,
+/// if it was asked to be exported
+pub fn export_replay() {
+    if !EXPORT_REQUESTED.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    // Disable interrupts to avoid deadlock
+    let last_replay = interrupts::without_interrupts(|| LAST_REPLAY.lock().clone());
+    let replay = match last_replay.as_ref() {
+        Some(replay) => replay,
+        None => {
+            serial_println!("No game to export yet");
+            return;
+        }
+    };
+    let bytes = replay.as_bytes();
+    serial_println!(
+        "Replay of game with seed {}, {} bytes, checksum {:04X}:",
+        replay.seed(),
+        bytes.len(),
+        record::checksum(bytes)
+    );
+    for line in bytes.chunks(EXPORT_LINE_BYTES) {
+        for byte in line {
+            serial_print!("{:02X}", byte);
+        }
+        serial_println!();
+    }
+    serial_println!("End of replay");
+}
+
+/// Choose speed level for the next game while the current one is over
+fn set_start_speed(level: u8, screen: &Mutex<Writer>) {
+    let mut game = GAME.lock();
+    if can_choose_options(&game) {
+        game.set_start_speed(level);
+        redraw(&game, screen);
+    }
+}
+
+/// Choose the next level to start from, going back to the first after the last one, while the game is over
+fn cycle_start_level(screen: &Mutex<Writer>) {
+    let mut game = GAME.lock();
+    if can_choose_options(&game) {
+        let config = game.config();
+        let level = (config.start_level + 1) % config.levels.len();
+        game.set_start_level(level);
+        redraw(&game, screen);
+    }
+}
+
+/// Switch between solid and wrapping edges for the next game while the current one is over
+fn toggle_edges(screen: &Mutex<Writer>) {
+    let mut game = GAME.lock();
+    if can_choose_options(&game) {
+        let edges = match game.config().edges {
+            Edges::Solid => Edges::Wrap,
+            Edges::Wrap => Edges::Solid,
+        };
+        game.set_edges(edges);
+        redraw(&game, screen);
+    }
+}
+
+/// Switch between one and two players for the next game while the current one is over
+fn toggle_players(screen: &Mutex<Writer>) {
+    let mut game = GAME.lock();
+    if can_choose_options(&game) {
+        let players = game.config().player_count() % MAX_PLAYERS + 1;
+        game.set_players(players);
+        redraw(&game, screen);
+    }
+}
+
+/// Choose the next difficulty of the computer opponent, or no opponent after the hardest one, while the game is over.
+/// Choosing an opponent makes the next game a two-player one.
+fn cycle_opponent(screen: &Mutex<Writer>) {
+    let mut game = GAME.lock();
+    if can_choose_options(&game) {
+        let difficulties = &autopilot::DIFFICULTIES;
+        let opponent: Option<Difficulty> = match game.config().opponent {
+            None => Some(difficulties[0]),
+            Some(difficulty) => difficulties
+                .iter()
+                .position(|other| *other == difficulty)
+                .and_then(|index| difficulties.get(index + 1))
+                .copied(),
+        };
+        game.set_opponent(opponent);
+        if opponent.is_some() {
+            game.set_players(MAX_PLAYERS);
+        }
+        redraw(&game, screen);
+    }
+}
+
+/// Pause or resume the game. Drawing the board again on resume erases the pause banner.
+fn toggle_pause(screen: &Mutex<Writer>) {
+    match current_screen() {
+        Screen::Play | Screen::Replay { .. } => (),
+        Screen::Title { .. } | Screen::HighScores | Screen::Controls { .. } | Screen::Demo => {
+            return
+        }
+    }
+    let mut game = GAME.lock();
+    game.toggle_pause();
+    match game.state() {
+        State::Paused => serial_println!("Game paused"),
+        State::Playing => serial_println!("Game resumed"),
+        State::GameOver | State::Won => (),
+    }
+    redraw(&game, screen);
+}
+
+/// Queue a direction for given player's snake to turn to on an upcoming step
+fn set_turn_direction(player: usize, turn_direction: Direction) {
+    GAME.lock()
+        .set_player_turn_direction(player, turn_direction);
+}
@@ -0,0 +1,46 @@
+/* Seeds from the random number generator built into the CPU
+//
+// RDSEED reads the CPU's entropy source directly, and RDRAND a generator
+// reseeded from it. Either may be missing, which CPUID reports, and either may
+// fail for a moment when drained, in which case it is asked again a few times.
+// Based upon https://software.intel.com/content/www/us/en/develop/articles/intel-digital-random-number-generator-drng-software-implementation-guide.html
+*/
+
+use core::arch::x86_64::{__cpuid, __cpuid_count, _rdseed64_step};
+use x86_64::instructions::random::RdRand;
+
+// CPUID leaf 7, sub-leaf 0, reports RDSEED support in this bit of ebx
+const RDSEED_BIT: u32 = 1 << 18;
+// Times to ask the generator again after it failed to deliver
+const RETRIES: usize = 10;
+
+/// Check if the CPU has the RDSEED instruction
+fn has_rdseed() -> bool {
+    // Leaf 7 only exists if leaf 0 reports it as supported
+    unsafe { __cpuid(0).eax >= 7 && __cpuid_count(7, 0).ebx & RDSEED_BIT != 0 }
+}
+
+/// Read a seed with RDSEED, if the CPU supports it
+fn rdseed() -> Option<u64> {
+    if !has_rdseed() {
+        return None;
+    }
+    let mut seed = 0;
+    for _ in 0..RETRIES {
+        if unsafe { _rdseed64_step(&mut seed) } == 1 {
+            return Some(seed);
+        }
+    }
+    None
+}
+
+/// Read a seed with RDRAND, if the CPU supports it
+fn rdrand() -> Option<u64> {
+    let rdrand = RdRand::new()?;
+    (0..RETRIES).filter_map(|_| rdrand.get_u64()).next()
+}
+
+/// Get a random seed from the CPU, preferring RDSEED over RDRAND. None if neither is available.
+pub fn get_seed() -> Option<u64> {
+    rdseed().or_else(rdrand)
+}
@@ -9,14 +9,17 @@
 
 mod gdt;
 mod idt;
+mod pit;
 
-use crate::println;
+use crate::serial_println;
 use lazy_static::lazy_static;
 use pic8259_simple::ChainedPics;
+use snake_core::bindings::Key;
+use snake_core::speed;
 use spin;
 use vga_buffer::VGA_WRITER;
 
-use crate::snake::{Direction, SNAKE};
+use crate::game;
 
 // New offset for interrupts from PIC1 of chained PICs
 pub const PIC_1_OFFSET: u8 = 32;
@@ -175,7 +178,7 @@ extern "C" fn divide_by_zero_handler(stack_frame: &ExceptionStackFrame) {
 
 /// Handler when execution reaches an invalid opcode during execution
 extern "C" fn invalid_opcode_handler(stack_frame: &ExceptionStackFrame) {
-    println!(
+    serial_println!(
         "EXCEPTION: INVALID OPCODE at {:#x}\n{:#?}",
         stack_frame.instruction_pointer, stack_frame
     );
@@ -195,7 +198,7 @@ extern "C" fn page_fault_handler(stack_frame: &ExceptionStackFrame, error_code:
 
 /// Handle breakpoints during execution
 extern "C" fn breakpoint_handler(stack_frame: &ExceptionStackFrame) {
-    println!(
+    serial_println!(
         "EXCEPTION: BREAKPOINT at {:#x}\n{:#?}",
         stack_frame.instruction_pointer, stack_frame
     );
@@ -211,9 +214,7 @@ extern "C" fn double_fault_handler(stack_frame: &ExceptionStackFrame, error_code
 
 /// Handle Timer interrupts from Intel 8259 PIC
 extern "C" fn timer_interrupt_handler(_stack_frame: &ExceptionStackFrame) {
-    {
-        SNAKE.lock().tick(&VGA_WRITER);
-    }
+    game::tick(&VGA_WRITER);
     unsafe {
         PICS.lock()
             .notify_end_of_interrupt(InterruptIndex::Timer.as_u8());
@@ -237,17 +238,32 @@ extern "C" fn keyboard_interrupt_handler(_stack_frame: &ExceptionStackFrame) {
     let scancode: u8 = unsafe { port.read() };
 
     if let Ok(Some(key_event)) = keyboard.add_byte(scancode) {
-        if let Some(key) = keyboard.process_keyevent(key_event) {
-            match key {
-                DecodedKey::Unicode(_) => (),
-                DecodedKey::RawKey(key) => match key {
-                    KeyCode::ArrowUp => SNAKE.lock().set_turn_direction(Direction::Up),
-                    KeyCode::ArrowDown => SNAKE.lock().set_turn_direction(Direction::Down),
-                    KeyCode::ArrowLeft => SNAKE.lock().set_turn_direction(Direction::Left),
-                    KeyCode::ArrowRight => SNAKE.lock().set_turn_direction(Direction::Right),
-                    _ => (),
-                },
+        // A key pressed during a demo game only ends it
+        let key = keyboard
+            .process_keyevent(key_event)
+            .filter(|_| !game::note_key_press(&VGA_WRITER));
+        match key {
+            // Typed keys make up initials until a high score is saved
+            Some(DecodedKey::Unicode(character)) if game::is_entering_initials() => {
+                game::enter_initials_key(character, &VGA_WRITER)
+            }
+            Some(DecodedKey::Unicode(character)) => {
+                if let Some(key) = Key::from_char(character) {
+                    game::press_key(key, &VGA_WRITER)
+                }
             }
+            Some(DecodedKey::RawKey(key)) => match key {
+                KeyCode::ArrowUp => game::press_key(Key::Up, &VGA_WRITER),
+                KeyCode::ArrowDown => game::press_key(Key::Down, &VGA_WRITER),
+                KeyCode::ArrowLeft => game::press_key(Key::Left, &VGA_WRITER),
+                KeyCode::ArrowRight => game::press_key(Key::Right, &VGA_WRITER),
+                KeyCode::Escape if game::is_entering_initials() => {
+                    game::enter_initials_key('\u{1b}', &VGA_WRITER)
+                }
+                KeyCode::Escape => game::press_key(Key::ESCAPE, &VGA_WRITER),
+                _ => (),
+            },
+            None => (),
         }
     }
 
@@ -262,5 +278,15 @@ pub fn init() {
     gdt::init();
     IDT.load();
     unsafe { PICS.lock().initialize() };
+    pit::set_frequency(speed::TICKS_PER_SECOND);
     x86_64::instructions::interrupts::enable();
 }
+
+#[cfg(test)]
+mod tests {
+    #[test_case]
+    fn breakpoint_exception_returns() {
+        // Execution continues after breakpoint handler returns
+        x86_64::instructions::interrupts::int3();
+    }
+}
@@ -0,0 +1,33 @@
+/* Driver for Intel 8253/8254 Programmable Interval Timer
+//
+// Channel 0 of the PIT is wired to IRQ 0 of the chained PICs, and drives the
+// timer interrupt.
+// Based upon https://wiki.osdev.org/Programmable_Interval_Timer
+*/
+
+use x86_64::instructions::port::Port;
+
+const CHANNEL_0_DATA_PORT: u16 = 0x40;
+const MODE_COMMAND_PORT: u16 = 0x43;
+// Frequency of the oscillator feeding the PIT, in Hz
+const BASE_FREQUENCY: u32 = 1_193_182;
+// Select channel 0, access mode lobyte/hibyte, operating mode 3 (square wave generator), binary mode
+const CHANNEL_0_SQUARE_WAVE_COMMAND: u8 = 0b0011_0110;
+
+/// Program channel 0 to raise timer interrupts at given frequency in Hz
+pub fn set_frequency(frequency: u32) {
+    let mut divisor = BASE_FREQUENCY / frequency;
+    // Divisor is a 16 bit value, where 0 stands for 65536
+    if divisor > 0xFFFF {
+        divisor = 0;
+    } else if divisor == 0 {
+        divisor = 1;
+    }
+    let mut command_port = Port::new(MODE_COMMAND_PORT);
+    let mut data_port = Port::new(CHANNEL_0_DATA_PORT);
+    unsafe {
+        command_port.write(CHANNEL_0_SQUARE_WAVE_COMMAND);
+        data_port.write((divisor & 0xFF) as u8);
+        data_port.write((divisor >> 8) as u8);
+    }
+}
@@ -10,7 +10,14 @@
 #![feature(asm)]
 #![feature(naked_functions)]
 #![feature(core_intrinsics)]
+#![feature(alloc_error_handler)]
+#![feature(custom_test_frameworks)]
+#![test_runner(crate::testing::test_runner)]
+#![reexport_test_harness_main = "test_main"]
+#![cfg_attr(test, no_main)]
 
+#[macro_use]
+extern crate alloc;
 extern crate bit_field;
 extern crate lazy_static;
 extern crate spin;
@@ -21,41 +28,90 @@ extern crate x86_64;
 extern crate bitflags;
 extern crate pc_keyboard;
 extern crate pic8259_simple;
+extern crate snake_core;
 
 use core::panic::PanicInfo;
+use x86_64::instructions::port::Port;
 
+mod allocator;
+mod ata;
+mod boot_options;
 mod boundary;
+mod game;
+mod hardware_random;
 mod interrupts;
-mod prng;
-mod ring_buffer;
-mod score;
-mod snake;
+mod menu;
+mod multiboot;
+mod nvram;
+mod renderer;
+mod serial;
+mod storage;
 mod system_time;
+#[cfg(test)]
+mod testing;
 mod vga_buffer;
 
-use snake::SNAKE;
 use vga_buffer::VGA_WRITER;
 
+#[cfg(not(test))]
 #[panic_handler]
 fn panic(info: &PanicInfo) -> ! {
+    // Serial port first, in case the panic happened while VGA buffer was locked
+    serial_println!("{}", info);
     print!("{}", info);
     hlt_loop();
 }
 
+#[cfg(test)]
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    testing::test_panic_handler(info)
+}
+
+#[alloc_error_handler]
+fn alloc_error_handler(layout: alloc::alloc::Layout) -> ! {
+    panic!("Allocation error: {:?}", layout)
+}
+
+#[cfg(not(test))]
 #[no_mangle]
-pub extern "C" fn rust_main() {
+pub extern "C" fn rust_main(multiboot_information_address: usize) {
+    allocator::init();
+    let boot_information = unsafe { multiboot::load(multiboot_information_address) };
+    multiboot::log_boot_information(&boot_information);
+    boot_options::init(&boot_information);
     VGA_WRITER.lock().clear_screen();
 
-    let boundary = boundary::Boundary {};
-    let score = score::Score::new(0);
-    score.draw(&VGA_WRITER);
-    boundary.draw(&VGA_WRITER);
+    // Reading the disk takes long, so it is done before interrupts are enabled
+    game::load();
     // Interrupts are not enabled until this point, therefore no need of disabling them while using `VGA_WRITER` to avoid deadlock
-    SNAKE.lock().draw(&VGA_WRITER);
-    SNAKE.lock().set_score_handler(score);
+    game::draw(&VGA_WRITER);
+
+    interrupts::init();
+
+    // Disk writes and replay exports take long, so they are done here between interrupts rather than in their handlers
+    loop {
+        x86_64::instructions::hlt();
+        game::save_high_scores();
+        game::save_bindings();
+        game::save_replay();
+        game::export_replay();
+    }
+}
 
+/// Entry point of the kernel built for running tests inside QEMU
+#[cfg(test)]
+#[no_mangle]
+pub extern "C" fn rust_main(multiboot_information_address: usize) {
+    allocator::init();
+    let boot_information = unsafe { multiboot::load(multiboot_information_address) };
+    multiboot::log_boot_information(&boot_information);
+    boot_options::init(&boot_information);
+    VGA_WRITER.lock().clear_screen();
     interrupts::init();
 
+    test_main();
+
     hlt_loop();
 }
 
@@ -64,3 +120,16 @@ pub fn hlt_loop() -> ! {
         x86_64::instructions::hlt();
     }
 }
+
+// Keyboard controller's command port, and the command pulsing the CPU's reset line
+const KEYBOARD_CONTROLLER_PORT: u16 = 0x64;
+const RESET_COMMAND: u8 = 0xFE;
+
+/// Restart the machine through the keyboard controller
+pub fn reboot() -> ! {
+    let mut port = Port::new(KEYBOARD_CONTROLLER_PORT);
+    unsafe {
+        port.write(RESET_COMMAND);
+    }
+    hlt_loop();
+}
@@ -0,0 +1,112 @@
+/* Menu element for the game
+//
+// A menu is a column of items, one of which is selected and highlighted. Its
+// owner keeps the selection, and hands over labels of the items whenever the
+// menu is drawn, so that labels can show values which change, like the speed
+// chosen for the next game.
+*/
+
+use core::fmt::Write;
+use spin::Mutex;
+use x86_64::instructions::interrupts;
+
+use crate::vga_buffer::{Color, ColorCode, LineBuffer, Writer, BUFFER_WIDTH};
+
+// Blank cells on each side of the longest label
+const MARGIN: usize = 2;
+
+/// Selection in a column of items
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Menu {
+    /// Number of items
+    len: usize,
+    /// Index of the selected item
+    selected: usize,
+}
+
+impl Menu {
+    /// Create a menu of given number of items, with the first one selected
+    pub fn new(len: usize) -> Self {
+        Menu { len, selected: 0 }
+    }
+
+    /// Get the index of the selected item
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Select the item above, or the last one from the first
+    pub fn select_previous(&mut self) {
+        self.selected = (self.selected + self.len - 1) % self.len;
+    }
+
+    /// Select the item below, or the first one from the last
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.len;
+    }
+
+    /// Draw given labels of the items centered on screen, one per row from `first_row` on,
+    /// with the selected one highlighted
+    pub fn draw(&self, labels: &[&str], first_row: usize, screen: &Mutex<Writer>) {
+        let width = labels.iter().map(|label| label.len()).max().unwrap_or(0) + 2 * MARGIN;
+        let first_col = (BUFFER_WIDTH - width) / 2;
+        // Disable interrupts to avoid deadlock
+        interrupts::without_interrupts(|| {
+            let mut writer = screen.lock();
+            for (i, label) in labels.iter().enumerate() {
+                let (marks, color_code) = if i == self.selected {
+                    (('>', '<'), ColorCode::new(Color::Black, Color::Yellow))
+                } else {
+                    ((' ', ' '), ColorCode::new(Color::White, Color::Black))
+                };
+                let mut line = LineBuffer::new();
+                let _ = write!(
+                    line,
+                    "{}{:^width$}{}",
+                    marks.0,
+                    label,
+                    marks.1,
+                    width = width - 2
+                );
+                writer.write_colored_string_at(line.as_str(), color_code, first_row + i, first_col);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vga_buffer::VGA_WRITER;
+
+    #[test_case]
+    fn selection_wraps_around() {
+        let mut menu = Menu::new(3);
+        menu.select_previous();
+        assert_eq!(menu.selected(), 2);
+        menu.select_next();
+        assert_eq!(menu.selected(), 0);
+        menu.select_next();
+        assert_eq!(menu.selected(), 1);
+    }
+
+    #[test_case]
+    fn selected_item_is_marked() {
+        let mut menu = Menu::new(2);
+        menu.select_next();
+        interrupts::without_interrupts(|| {
+            menu.draw(&["FIRST", "SECOND"], 3, &VGA_WRITER);
+            let writer = VGA_WRITER.lock();
+            // Items are padded to the longest label, "SECOND"
+            let first_col = (BUFFER_WIDTH - 6 - 2 * MARGIN) / 2;
+            assert_eq!(writer.read_character_at(3, first_col).ascii_character, b' ');
+            assert_eq!(writer.read_character_at(4, first_col).ascii_character, b'>');
+            assert_eq!(
+                writer
+                    .read_character_at(4, first_col + MARGIN)
+                    .ascii_character,
+                b'S'
+            );
+        });
+    }
+}
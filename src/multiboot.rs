@@ -0,0 +1,429 @@
+/* Parser for the boot information structure handed over by a Multiboot2 loader
+//
+// The structure starts with its total size, followed by tags of varying size,
+// each aligned to 8 bytes and starting with its type and size, and ends with a
+// tag of type 0. Fields are read out of a byte slice instead of casting the
+// memory to structs, so a malformed tag can never be read past the end.
+// Based upon https://www.gnu.org/software/grub/manual/multiboot2/multiboot.html
+*/
+
+use core::slice;
+use core::str;
+
+use crate::serial_println;
+
+const END_TAG: u32 = 0;
+const COMMAND_LINE_TAG: u32 = 1;
+const BOOTLOADER_NAME_TAG: u32 = 2;
+const MEMORY_MAP_TAG: u32 = 6;
+const FRAMEBUFFER_TAG: u32 = 8;
+const ELF_SECTIONS_TAG: u32 = 9;
+
+// Size of the fixed part in front of the tags, and in front of every tag
+const HEADER_SIZE: usize = 8;
+// Tags start at addresses aligned to this
+const TAG_ALIGNMENT: usize = 8;
+// Kind of ELF sections taking no room in the image, zeroed when loaded
+const ELF_SECTION_NOBITS: u32 = 8;
+
+/// Read a little endian u16 at given offset, or 0 if it lies outside of bytes
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    match bytes.get(offset..(offset + 2)) {
+        Some(field) => field[0] as u16 | (field[1] as u16) << 8,
+        None => 0,
+    }
+}
+
+/// Read a little endian u32 at given offset, or 0 if it lies outside of bytes
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    read_u16(bytes, offset) as u32 | (read_u16(bytes, offset + 2) as u32) << 16
+}
+
+/// Read a little endian u64 at given offset, or 0 if it lies outside of bytes
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    read_u32(bytes, offset) as u64 | (read_u32(bytes, offset + 4) as u64) << 32
+}
+
+/// Read a string terminated by a null byte, or None if it is not valid UTF-8
+fn read_str(bytes: &[u8]) -> Option<&str> {
+    let length = bytes
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(bytes.len());
+    str::from_utf8(&bytes[..length]).ok()
+}
+
+/// Boot information structure
+pub struct BootInformation<'a> {
+    bytes: &'a [u8],
+}
+
+/// Wrap the boot information structure at given address, as passed by the loader in `ebx`
+pub unsafe fn load(address: usize) -> BootInformation<'static> {
+    let total_size = *(address as *const u32) as usize;
+    BootInformation::from_bytes(slice::from_raw_parts(address as *const u8, total_size))
+}
+
+impl<'a> BootInformation<'a> {
+    /// Wrap bytes of a boot information structure, starting with its total size
+    pub fn from_bytes(bytes: &'a [u8]) -> Self {
+        let total_size = (read_u32(bytes, 0) as usize).min(bytes.len());
+        BootInformation {
+            bytes: &bytes[..total_size],
+        }
+    }
+
+    /// Iterate over the tags
+    pub fn tags(&self) -> TagIter<'a> {
+        TagIter {
+            bytes: self.bytes,
+            offset: HEADER_SIZE,
+        }
+    }
+
+    /// Find the first tag of given type
+    fn tag(&self, typ: u32) -> Option<Tag<'a>> {
+        self.tags().find(|tag| tag.typ == typ)
+    }
+
+    /// Command line the kernel was started with
+    pub fn command_line(&self) -> Option<&'a str> {
+        self.tag(COMMAND_LINE_TAG)
+            .and_then(|tag| read_str(tag.data()))
+    }
+
+    /// Name of the loader which started the kernel
+    pub fn bootloader_name(&self) -> Option<&'a str> {
+        self.tag(BOOTLOADER_NAME_TAG)
+            .and_then(|tag| read_str(tag.data()))
+    }
+
+    /// Areas of physical memory along with what they can be used for
+    pub fn memory_map(&self) -> Option<MemoryMap<'a>> {
+        self.tag(MEMORY_MAP_TAG).map(|tag| {
+            let data = tag.data();
+            MemoryMap {
+                entries: data.get(8..).unwrap_or(&[]),
+                entry_size: read_u32(data, 0) as usize,
+            }
+        })
+    }
+
+    /// Framebuffer the loader set up
+    pub fn framebuffer(&self) -> Option<Framebuffer> {
+        self.tag(FRAMEBUFFER_TAG).map(|tag| {
+            let data = tag.data();
+            Framebuffer {
+                address: read_u64(data, 0),
+                pitch: read_u32(data, 8),
+                width: read_u32(data, 12),
+                height: read_u32(data, 16),
+                bits_per_pixel: data.get(20).cloned().unwrap_or(0),
+                kind: match data.get(21) {
+                    Some(0) => FramebufferKind::Indexed,
+                    Some(1) => FramebufferKind::Rgb,
+                    _ => FramebufferKind::Text,
+                },
+            }
+        })
+    }
+
+    /// Section headers of the kernel's ELF image
+    pub fn elf_sections(&self) -> Option<ElfSections<'a>> {
+        self.tag(ELF_SECTIONS_TAG).map(|tag| {
+            let data = tag.data();
+            ElfSections {
+                headers: data.get(12..).unwrap_or(&[]),
+                count: read_u32(data, 0) as usize,
+                header_size: read_u32(data, 4) as usize,
+                index: 0,
+            }
+        })
+    }
+}
+
+/// Tag of the boot information structure
+#[derive(Clone, Copy)]
+pub struct Tag<'a> {
+    pub typ: u32,
+    /// Whole tag, including type and size
+    bytes: &'a [u8],
+}
+
+impl<'a> Tag<'a> {
+    /// Content of the tag following type and size
+    pub fn data(&self) -> &'a [u8] {
+        &self.bytes[HEADER_SIZE..]
+    }
+}
+
+/// Iterator over tags, stopping at the end tag
+pub struct TagIter<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Iterator for TagIter<'a> {
+    type Item = Tag<'a>;
+
+    fn next(&mut self) -> Option<Tag<'a>> {
+        let typ = read_u32(self.bytes, self.offset);
+        let size = read_u32(self.bytes, self.offset + 4) as usize;
+        if typ == END_TAG || size < HEADER_SIZE {
+            return None;
+        }
+        let bytes = self.bytes.get(self.offset..(self.offset + size))?;
+        // Next tag starts at an aligned address
+        self.offset += (size + TAG_ALIGNMENT - 1) & !(TAG_ALIGNMENT - 1);
+        Some(Tag { typ, bytes })
+    }
+}
+
+/// What an area of memory can be used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryAreaKind {
+    Available,
+    AcpiReclaimable,
+    /// Has to be preserved on hibernation
+    AcpiNvs,
+    Defective,
+    Reserved,
+}
+
+/// Area of physical memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryArea {
+    pub base_address: u64,
+    pub length: u64,
+    pub kind: MemoryAreaKind,
+}
+
+/// Iterator over areas of physical memory
+pub struct MemoryMap<'a> {
+    entries: &'a [u8],
+    entry_size: usize,
+}
+
+impl<'a> Iterator for MemoryMap<'a> {
+    type Item = MemoryArea;
+
+    fn next(&mut self) -> Option<MemoryArea> {
+        // Entries may grow in later versions, so the size given by the loader is used to skip them
+        if self.entry_size < 20 || self.entries.len() < self.entry_size {
+            return None;
+        }
+        let entry = &self.entries[..self.entry_size];
+        self.entries = &self.entries[self.entry_size..];
+        Some(MemoryArea {
+            base_address: read_u64(entry, 0),
+            length: read_u64(entry, 8),
+            kind: match read_u32(entry, 16) {
+                1 => MemoryAreaKind::Available,
+                3 => MemoryAreaKind::AcpiReclaimable,
+                4 => MemoryAreaKind::AcpiNvs,
+                5 => MemoryAreaKind::Defective,
+                _ => MemoryAreaKind::Reserved,
+            },
+        })
+    }
+}
+
+/// How pixels of a framebuffer are encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FramebufferKind {
+    /// Pixels are indices into a palette
+    Indexed,
+    /// Pixels hold red, green and blue values
+    Rgb,
+    /// EGA text mode, where every cell holds a character and its color
+    Text,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Framebuffer {
+    /// Physical address of the framebuffer
+    pub address: u64,
+    /// Bytes in a row
+    pub pitch: u32,
+    /// Pixels, or characters in text mode, in a row
+    pub width: u32,
+    /// Rows
+    pub height: u32,
+    pub bits_per_pixel: u8,
+    pub kind: FramebufferKind,
+}
+
+/// Section header of an ELF64 image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfSection {
+    pub kind: u32,
+    pub flags: u64,
+    /// Address of the section in memory
+    pub address: u64,
+    pub size: u64,
+}
+
+/// Iterator over section headers of the kernel's ELF image
+pub struct ElfSections<'a> {
+    headers: &'a [u8],
+    count: usize,
+    header_size: usize,
+    index: usize,
+}
+
+impl<'a> Iterator for ElfSections<'a> {
+    type Item = ElfSection;
+
+    fn next(&mut self) -> Option<ElfSection> {
+        if self.index >= self.count || self.header_size < 40 {
+            return None;
+        }
+        let offset = self.index * self.header_size;
+        let header = self.headers.get(offset..(offset + self.header_size))?;
+        self.index += 1;
+        Some(ElfSection {
+            kind: read_u32(header, 4),
+            flags: read_u64(header, 8),
+            address: read_u64(header, 16),
+            size: read_u64(header, 32),
+        })
+    }
+}
+
+/// Log what the loader told about the machine to serial port
+pub fn log_boot_information(boot_information: &BootInformation) {
+    if let Some(name) = boot_information.bootloader_name() {
+        serial_println!("Booted by {}", name);
+    }
+    if let Some(command_line) = boot_information.command_line() {
+        serial_println!("Command line: {}", command_line);
+    }
+    if let Some(memory_map) = boot_information.memory_map() {
+        let (available, end) = memory_map
+            .filter(|area| area.kind == MemoryAreaKind::Available)
+            .fold((0, 0), |(available, end), area| {
+                (
+                    available + area.length,
+                    end.max(area.base_address + area.length),
+                )
+            });
+        serial_println!(
+            "Available memory: {} KiB, up to {:#x}",
+            available / 1024,
+            end
+        );
+    }
+    if let Some(framebuffer) = boot_information.framebuffer() {
+        serial_println!(
+            "Framebuffer: {}x{} {:?} at {:#x}, {} bits per pixel, {} bytes a row",
+            framebuffer.width,
+            framebuffer.height,
+            framebuffer.kind,
+            framebuffer.address,
+            framebuffer.bits_per_pixel,
+            framebuffer.pitch
+        );
+    }
+    if let Some(sections) = boot_information.elf_sections() {
+        // Allocated sections of the image, which end up in memory
+        let mut kernel_end = 0;
+        let mut zeroed = 0;
+        for section in sections.filter(|section| section.flags & 0x2 != 0) {
+            kernel_end = kernel_end.max(section.address + section.size);
+            if section.kind == ELF_SECTION_NOBITS {
+                zeroed += section.size;
+            }
+        }
+        serial_println!(
+            "Kernel image ends at {:#x}, {} KiB of it zeroed when loaded",
+            kernel_end,
+            zeroed / 1024
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Boot information with command line, bootloader name, a memory map of two areas, and framebuffer tags
+    fn sample() -> [u8; 160] {
+        fn put_u32(bytes: &mut [u8; 160], offset: usize, value: u32) {
+            bytes[offset..(offset + 4)].copy_from_slice(&value.to_le_bytes());
+        }
+        let mut bytes = [0u8; 160];
+        put_u32(&mut bytes, 0, 160);
+        // Command line at 8, 8 + 9 bytes, padded to 24
+        put_u32(&mut bytes, 8, COMMAND_LINE_TAG);
+        put_u32(&mut bytes, 12, 17);
+        bytes[16..25].copy_from_slice(b"speed=9\0\0");
+        // Bootloader name at 32, 8 + 5 bytes, padded to 16
+        put_u32(&mut bytes, 32, BOOTLOADER_NAME_TAG);
+        put_u32(&mut bytes, 36, 13);
+        bytes[40..45].copy_from_slice(b"GRUB\0");
+        // Memory map at 48, 8 + 8 + 2 * 24 bytes
+        put_u32(&mut bytes, 48, MEMORY_MAP_TAG);
+        put_u32(&mut bytes, 52, 64);
+        put_u32(&mut bytes, 56, 24);
+        put_u32(&mut bytes, 64 + 8, 0x9fc00);
+        put_u32(&mut bytes, 64 + 16, 1);
+        put_u32(&mut bytes, 88, 0x100000);
+        put_u32(&mut bytes, 88 + 8, 0x7ee0000);
+        put_u32(&mut bytes, 88 + 16, 2);
+        // Framebuffer at 112, 8 + 22 bytes, padded to 32
+        put_u32(&mut bytes, 112, FRAMEBUFFER_TAG);
+        put_u32(&mut bytes, 116, 30);
+        put_u32(&mut bytes, 120, 0xb8000);
+        put_u32(&mut bytes, 128, 160);
+        put_u32(&mut bytes, 132, 80);
+        put_u32(&mut bytes, 136, 25);
+        bytes[140] = 16;
+        bytes[141] = 2;
+        // End tag at 144
+        put_u32(&mut bytes, 144, END_TAG);
+        put_u32(&mut bytes, 148, 8);
+        bytes
+    }
+
+    #[test_case]
+    fn tags_are_parsed() {
+        let bytes = sample();
+        let boot_information = BootInformation::from_bytes(&bytes);
+        assert_eq!(boot_information.tags().count(), 4);
+        assert_eq!(boot_information.command_line(), Some("speed=9"));
+        assert_eq!(boot_information.bootloader_name(), Some("GRUB"));
+        let mut memory_map = boot_information.memory_map().unwrap();
+        assert_eq!(
+            memory_map.next(),
+            Some(MemoryArea {
+                base_address: 0,
+                length: 0x9fc00,
+                kind: MemoryAreaKind::Available,
+            })
+        );
+        assert_eq!(
+            memory_map.next(),
+            Some(MemoryArea {
+                base_address: 0x100000,
+                length: 0x7ee0000,
+                kind: MemoryAreaKind::Reserved,
+            })
+        );
+        assert_eq!(memory_map.next(), None);
+        let framebuffer = boot_information.framebuffer().unwrap();
+        assert_eq!(framebuffer.kind, FramebufferKind::Text);
+        assert_eq!((framebuffer.width, framebuffer.height), (80, 25));
+        assert!(boot_information.elf_sections().is_none());
+    }
+
+    #[test_case]
+    fn truncated_structure_is_not_read_past_its_end() {
+        let bytes = sample();
+        // Total size cuts the memory map tag short
+        let mut truncated = bytes;
+        truncated[0] = 80;
+        let boot_information = BootInformation::from_bytes(&truncated);
+        assert_eq!(boot_information.tags().count(), 2);
+        assert!(boot_information.memory_map().is_none());
+    }
+}
@@ -0,0 +1,80 @@
+/* Module to keep data in battery backed CMOS memory across reboots
+//
+// Bytes outside the RTC and BIOS configuration registers are not used by the
+// firmware, so a small block of them holds the high scores.
+// Based upon https://wiki.osdev.org/CMOS
+*/
+
+use snake_core::high_scores::{HighScores, ENCODED_SIZE};
+use x86_64::instructions::interrupts;
+use x86_64::instructions::port::Port;
+
+use crate::system_time::{CMOS_ADDRESS_PORT, CMOS_DATA_PORT};
+
+// First CMOS register of the high scores block. Block must end before 0x80,
+// since the highest bit of the address port disables NMI.
+const HIGH_SCORES_REGISTER: u8 = 0x60;
+
+/// Read consecutive CMOS registers starting at given one
+fn read_registers(first_register: u8, bytes: &mut [u8]) {
+    let mut cmos_address_port = Port::new(CMOS_ADDRESS_PORT);
+    let mut cmos_data_port = Port::new(CMOS_DATA_PORT);
+    // Selecting a register and accessing it must not be interleaved with another access
+    interrupts::without_interrupts(|| {
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            unsafe {
+                cmos_address_port.write(first_register + i as u8);
+                *byte = cmos_data_port.read();
+            }
+        }
+    });
+}
+
+/// Write consecutive CMOS registers starting at given one
+fn write_registers(first_register: u8, bytes: &[u8]) {
+    let mut cmos_address_port = Port::new(CMOS_ADDRESS_PORT);
+    let mut cmos_data_port = Port::new(CMOS_DATA_PORT);
+    // Selecting a register and accessing it must not be interleaved with another access
+    interrupts::without_interrupts(|| {
+        for (i, byte) in bytes.iter().enumerate() {
+            unsafe {
+                cmos_address_port.write(first_register + i as u8);
+                cmos_data_port.write(*byte);
+            }
+        }
+    });
+}
+
+/// Read high scores, or an empty table if none were saved or they got corrupted
+pub fn read_high_scores() -> HighScores {
+    let mut bytes = [0; ENCODED_SIZE];
+    read_registers(HIGH_SCORES_REGISTER, &mut bytes);
+    HighScores::decode(&bytes).unwrap_or_default()
+}
+
+/// Save high scores to be read on next boot
+pub fn write_high_scores(high_scores: &HighScores) {
+    write_registers(HIGH_SCORES_REGISTER, &high_scores.encode());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snake_core::high_scores::Entry;
+
+    #[test_case]
+    fn high_scores_are_read_back() {
+        let mut high_scores = HighScores::new();
+        high_scores.insert(Entry {
+            initials: *b"QEM",
+            score: 42,
+        });
+        write_high_scores(&high_scores);
+        assert_eq!(read_high_scores(), high_scores);
+        // Damaged block is replaced by an empty table
+        let mut byte = [0];
+        read_registers(HIGH_SCORES_REGISTER, &mut byte);
+        write_registers(HIGH_SCORES_REGISTER, &[!byte[0]]);
+        assert_eq!(read_high_scores(), HighScores::new());
+    }
+}
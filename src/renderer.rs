@@ -0,0 +1,875 @@
+/* Module projecting the game model on the VGA buffer
+//
+// Game logic never reads back from the screen, so glyphs and colors used here can
+// be changed freely.
+*/
+
+use core::fmt::Write;
+use lazy_static::lazy_static;
+use spin::Mutex;
+use x86_64::instructions::interrupts;
+
+use snake_core::bindings::{Action, Bindings};
+use snake_core::board::{Board, BodyShape, Cell, Edges, Position, BOARD_HEIGHT, BOARD_WIDTH};
+use snake_core::food::FoodKind;
+use snake_core::game::{Game, State, MAX_PLAYERS};
+use snake_core::high_scores::TABLE_SIZE;
+use snake_core::score::Score;
+use snake_core::snake::Direction;
+use snake_core::speed::Speed;
+
+use crate::boundary::Boundary;
+use crate::game::{self, ControlsItem, TitleItem, CONTROLS_ITEMS, TITLE_ITEMS};
+use crate::menu::Menu;
+use crate::vga_buffer::{Color, ColorCode, LineBuffer, ScreenChar, Writer, BUFFER_WIDTH};
+
+// Screen row of the first board row, below score and top boundary
+const BOARD_FIRST_ROW: usize = 2;
+// Screen column of the first board column, right of left boundary
+const BOARD_FIRST_COL: usize = 1;
+
+const SCORE_ROW: usize = 0;
+const SCORE_LABEL: &str = "SCORE: ";
+// Number of digits in max score
+const SCORE_DIGITS: usize = 5;
+// Subtract length of SCORE_LABEL (7) + number of digits in max score (5)
+const SCORE_COL: usize = BUFFER_WIDTH - 12;
+
+const SPEED_ROW: usize = 0;
+const SPEED_COL: usize = 0;
+
+const SEED_ROW: usize = 0;
+const SEED_COL: usize = 10;
+// Wide enough for the largest seed, so a shorter one covers it up
+const SEED_WIDTH: usize = 26;
+
+const LEVEL_ROW: usize = 0;
+const LEVEL_COL: usize = 38;
+// Wide enough for the longest level name, so a shorter one covers it up
+const LEVEL_WIDTH: usize = 22;
+
+// Scores of both players take the place of the score, growing to the left up to the level name
+const PLAYER_SCORES_ROW: usize = 0;
+const PLAYER_SCORES_COL: usize = LEVEL_COL + LEVEL_WIDTH;
+// Captions of demo games and replays, over the bottom boundary
+const CAPTION_ROW: usize = BOARD_FIRST_ROW + BOARD_HEIGHT;
+const DEMO_CAPTION: &str = " DEMO - PRESS ANY KEY ";
+const REPLAY_CAPTION: &str = " REPLAY - PRESS F TO FAST-FORWARD ";
+const FAST_FORWARD_CAPTION: &str = " FAST REPLAY - PRESS F FOR NORMAL SPEED ";
+// Colors telling snakes and scores of the players apart
+const PLAYER_COLORS: [Color; MAX_PLAYERS] = [Color::White, Color::Pink];
+
+// Title screen, from the top of the board down
+const LOGO_ROW: usize = BOARD_FIRST_ROW + 1;
+const LOGO: [&str; 5] = [
+    " ####  #   #   ###   #   #  #####",
+    "#      ##  #  #   #  #  #   #    ",
+    " ###   # # #  #####  ###    #### ",
+    "    #  #  ##  #   #  #  #   #    ",
+    "####   #   #  #   #  #   #  #####",
+];
+const MENU_ROW: usize = LOGO_ROW + LOGO.len() + 2;
+const TITLE_HINTS_ROW: usize = MENU_ROW + TITLE_ITEMS.len() + 2;
+const MENU_HINT: &str = "UP/DOWN TO CHOOSE, LEFT/RIGHT TO CHANGE, ENTER TO SELECT";
+const REPLAY_HINT: &str = "PRESS R TO WATCH THE LAST GAME, X TO EXPORT IT";
+const BACK_HINT: &str = "PRESS ANY KEY TO GO BACK";
+// Controls page, from the top of the board down
+const CONTROLS_ROW: usize = BOARD_FIRST_ROW + 1;
+const CONTROLS_MENU_ROW: usize = CONTROLS_ROW + 2;
+const CONTROLS_HINTS_ROW: usize = CONTROLS_MENU_ROW + CONTROLS_ITEMS + 1;
+const CONTROLS_HINT: &str = "UP/DOWN TO CHOOSE, ENTER TO REBIND, ESC TO GO BACK";
+const OPTION_KEYS_HINT: &str =
+    "1-9 SPEED  L LEVEL  M EDGES  T PLAYERS  C COMPUTER  R REPLAY  X EXPORT";
+// Width of action names, lining keys up after them
+const ACTION_NAME_WIDTH: usize = 12;
+const INITIALS_HINT: &str = "TYPE YOUR INITIALS AND PRESS ENTER, OR ESC TO SKIP";
+
+lazy_static! {
+    /// Character to represent head of snake when moving up
+    static ref HEAD_UP_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 30,
+        color_code: ColorCode::new(Color::White, Color::Black),
+    };
+
+    /// Character to represent head of snake when moving left
+    static ref HEAD_LEFT_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 17,
+        color_code: ColorCode::new(Color::White, Color::Black),
+    };
+
+    /// Character to represent head of snake when moving right
+    static ref HEAD_RIGHT_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 16,
+        color_code: ColorCode::new(Color::White, Color::Black),
+    };
+
+    /// Character to represent head of snake when moving down
+    static ref HEAD_DOWN_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 31,
+        color_code: ColorCode::new(Color::White, Color::Black),
+    };
+
+    /// Character to represent snake's body connecting upper and left cells
+    static ref UP_LEFT_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 217,
+        color_code: ColorCode::new(Color::White, Color::Black),
+    };
+
+    /// Character to represent snake's body connecting upper and right cells
+    static ref UP_RIGHT_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 192,
+        color_code: ColorCode::new(Color::White, Color::Black),
+    };
+
+    /// Character to represent snake's body connecting lower and left cells
+    static ref DOWN_LEFT_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 191,
+        color_code: ColorCode::new(Color::White, Color::Black),
+    };
+
+    /// Character to represent snake's body connecting lower and right cells
+    static ref DOWN_RIGHT_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 218,
+        color_code: ColorCode::new(Color::White, Color::Black),
+    };
+
+    /// Character to represent horizonal parts of snake's body
+    static ref HORIZONTAL_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 196,
+        color_code: ColorCode::new(Color::White, Color::Black),
+    };
+
+    /// Character to represent vertical parts of snake's body
+    static ref VERTICAL_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 179,
+        color_code: ColorCode::new(Color::White, Color::Black),
+    };
+
+    /// Character to represent empty space where snake can move freely
+    static ref EMPTY_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 32,
+        color_code: ColorCode::new(Color::Black, Color::Black),
+    };
+
+    /// Character to represent food eaten by snake for incrementing score
+    static ref FOOD_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 3,
+        color_code: ColorCode::new(Color::Red, Color::Black),
+    };
+
+    /// Character to represent bonus food, worth more than regular food
+    static ref BONUS_FOOD_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 4,
+        color_code: ColorCode::new(Color::Yellow, Color::Black),
+    };
+
+    /// Character to represent food making the snake shorter
+    static ref SLIM_FOOD_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 9,
+        color_code: ColorCode::new(Color::LightCyan, Color::Black),
+    };
+
+    /// Character to represent food making the snake faster for a while
+    static ref SPEED_FOOD_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 175,
+        color_code: ColorCode::new(Color::LightGreen, Color::Black),
+    };
+
+    /// Character to represent walls inside the boundary
+    static ref WALL_CHARACTER: ScreenChar = ScreenChar {
+        ascii_character: 219,
+        color_code: ColorCode::new(Color::LightGray, Color::Black),
+    };
+}
+
+/// Character used to display given cell
+fn cell_character(cell: Cell) -> ScreenChar {
+    match cell {
+        Cell::Empty => *EMPTY_CHARACTER,
+        Cell::Wall => *WALL_CHARACTER,
+        Cell::Food(FoodKind::Regular) => *FOOD_CHARACTER,
+        Cell::Food(FoodKind::Bonus) => *BONUS_FOOD_CHARACTER,
+        Cell::Food(FoodKind::Slim) => *SLIM_FOOD_CHARACTER,
+        Cell::Food(FoodKind::Speed) => *SPEED_FOOD_CHARACTER,
+        Cell::Head(Direction::Up) => *HEAD_UP_CHARACTER,
+        Cell::Head(Direction::Down) => *HEAD_DOWN_CHARACTER,
+        Cell::Head(Direction::Left) => *HEAD_LEFT_CHARACTER,
+        Cell::Head(Direction::Right) => *HEAD_RIGHT_CHARACTER,
+        Cell::Body(BodyShape::Horizontal) => *HORIZONTAL_CHARACTER,
+        Cell::Body(BodyShape::Vertical) => *VERTICAL_CHARACTER,
+        Cell::Body(BodyShape::UpLeft) => *UP_LEFT_CHARACTER,
+        Cell::Body(BodyShape::UpRight) => *UP_RIGHT_CHARACTER,
+        Cell::Body(BodyShape::DownLeft) => *DOWN_LEFT_CHARACTER,
+        Cell::Body(BodyShape::DownRight) => *DOWN_RIGHT_CHARACTER,
+    }
+}
+
+/// Draw every cell of the board inside the boundary
+pub fn draw_board(board: &Board, screen: &Mutex<Writer>) {
+    // Disable interrupts to avoid deadlock
+    interrupts::without_interrupts(|| {
+        let mut writer = screen.lock();
+        for row in 0..BOARD_HEIGHT {
+            for col in 0..BOARD_WIDTH {
+                writer.write_character_at(
+                    cell_character(board.get(Position { row, col })),
+                    BOARD_FIRST_ROW + row,
+                    BOARD_FIRST_COL + col,
+                );
+            }
+        }
+    });
+}
+
+/// Draw the score at top-right corner on the screen
+pub fn draw_score(score: &Score, screen: &Mutex<Writer>) {
+    let mut line = LineBuffer::new();
+    // Pad the value to clear digits left behind by a previous game
+    let _ = write!(
+        line,
+        "{}{:>width$}",
+        SCORE_LABEL,
+        score.get_score(),
+        width = SCORE_DIGITS
+    );
+    // Disable interrupts to avoid deadlock
+    interrupts::without_interrupts(|| {
+        screen.lock().write_colored_string_at(
+            line.as_str(),
+            ColorCode::new(Color::White, Color::Black),
+            SCORE_ROW,
+            SCORE_COL,
+        );
+    });
+}
+
+/// Draw scores of all players at top-right corner on the screen, each in the color of their snake.
+/// A single player's score is drawn like before, clearing what two players' scores left behind.
+pub fn draw_scores(game: &Game, screen: &Mutex<Writer>) {
+    if game.players() == 1 {
+        let mut blank = LineBuffer::new();
+        let _ = write!(
+            blank,
+            "{:width$}",
+            "",
+            width = SCORE_COL - PLAYER_SCORES_COL
+        );
+        // Disable interrupts to avoid deadlock
+        interrupts::without_interrupts(|| {
+            screen.lock().write_colored_string_at(
+                blank.as_str(),
+                ColorCode::new(Color::Black, Color::Black),
+                PLAYER_SCORES_ROW,
+                PLAYER_SCORES_COL,
+            );
+        });
+        draw_score(game.score(), screen);
+        return;
+    }
+    let mut col = PLAYER_SCORES_COL;
+    for player in 0..game.players() {
+        let mut line = LineBuffer::new();
+        let _ = write!(
+            line,
+            "{}{}P:{:>width$}",
+            if player > 0 { "  " } else { "" },
+            player + 1,
+            game.score_of(player).get_score(),
+            width = SCORE_DIGITS + 1
+        );
+        // Disable interrupts to avoid deadlock
+        interrupts::without_interrupts(|| {
+            screen.lock().write_colored_string_at(
+                line.as_str(),
+                ColorCode::new(PLAYER_COLORS[player], Color::Black),
+                PLAYER_SCORES_ROW,
+                col,
+            );
+        });
+        col += line.as_str().len();
+    }
+}
+
+/// Draw snakes of players other than the first one again, in their own colors
+fn draw_player_snakes(game: &Game, screen: &Mutex<Writer>) {
+    // Disable interrupts to avoid deadlock
+    interrupts::without_interrupts(|| {
+        let mut writer = screen.lock();
+        for player in 1..game.players() {
+            for position in game.snake_of(player).positions() {
+                let mut character = cell_character(game.board().get(position));
+                character.color_code = ColorCode::new(PLAYER_COLORS[player], Color::Black);
+                writer.write_character_at(
+                    character,
+                    BOARD_FIRST_ROW + position.row,
+                    BOARD_FIRST_COL + position.col,
+                );
+            }
+        }
+    });
+}
+
+/// Draw the speed level at top-left corner on the screen, highlighted while boosted by speed food
+pub fn draw_speed(speed: &Speed, screen: &Mutex<Writer>) {
+    let mut line = LineBuffer::new();
+    let _ = write!(line, "SPEED: {}", speed.effective_level());
+    let color = if speed.is_boosted() {
+        Color::LightGreen
+    } else {
+        Color::White
+    };
+    // Disable interrupts to avoid deadlock
+    interrupts::without_interrupts(|| {
+        screen.lock().write_colored_string_at(
+            line.as_str(),
+            ColorCode::new(color, Color::Black),
+            SPEED_ROW,
+            SPEED_COL,
+        );
+    });
+}
+
+/// Draw the seed food placement of the game started from, next to the speed level
+pub fn draw_seed(seed: u64, screen: &Mutex<Writer>) {
+    let mut line = LineBuffer::new();
+    let _ = write!(line, "SEED: {:<width$}", seed, width = SEED_WIDTH - 6);
+    // Disable interrupts to avoid deadlock
+    interrupts::without_interrupts(|| {
+        screen.lock().write_colored_string_at(
+            line.as_str(),
+            ColorCode::new(Color::DarkGray, Color::Black),
+            SEED_ROW,
+            SEED_COL,
+        );
+    });
+}
+
+/// Draw number and name of the level being played at top of the screen
+pub fn draw_level(game: &Game, screen: &Mutex<Writer>) {
+    let mut line = LineBuffer::new();
+    let _ = write!(line, "LEVEL {}: {}", game.level_number(), game.level().name);
+    let mut padded = LineBuffer::new();
+    let _ = write!(padded, "{:<width$}", line.as_str(), width = LEVEL_WIDTH);
+    // Disable interrupts to avoid deadlock
+    interrupts::without_interrupts(|| {
+        screen.lock().write_colored_string_at(
+            padded.as_str(),
+            ColorCode::new(Color::White, Color::Black),
+            LEVEL_ROW,
+            LEVEL_COL,
+        );
+    });
+}
+
+/// Draw lines of text centered on the board, inside a box covering the cells underneath
+pub fn draw_banner(lines: &[&str], screen: &Mutex<Writer>) {
+    let color_code = ColorCode::new(Color::White, Color::Blue);
+    let blank = ScreenChar {
+        ascii_character: b' ',
+        color_code,
+    };
+    let mut width = 0;
+    for line in lines {
+        if line.len() > width {
+            width = line.len();
+        }
+    }
+    // Leave a margin of blank cells around the text
+    let width = width + 4;
+    let height = lines.len() + 2;
+    let first_row = BOARD_FIRST_ROW + (BOARD_HEIGHT - height) / 2;
+    let first_col = BOARD_FIRST_COL + (BOARD_WIDTH - width) / 2;
+    // Disable interrupts to avoid deadlock
+    interrupts::without_interrupts(|| {
+        let mut writer = screen.lock();
+        for row in first_row..(first_row + height) {
+            for col in first_col..(first_col + width) {
+                writer.write_character_at(blank, row, col);
+            }
+        }
+        for (i, line) in lines.iter().enumerate() {
+            writer.write_colored_string_at(
+                line,
+                color_code,
+                first_row + 1 + i,
+                first_col + (width - line.len()) / 2,
+            );
+        }
+    });
+}
+
+/// Lines telling which keys choose options of the next game, along with the options chosen
+fn option_hints(game: &Game) -> [LineBuffer; 5] {
+    let config = game.config();
+    let mut hints = [LineBuffer::new(); 5];
+    let _ = write!(
+        hints[0],
+        "PRESS 1-9 TO CHOOSE STARTING SPEED ({})",
+        config.start_speed
+    );
+    let start_level = config.first_level();
+    let _ = write!(
+        hints[1],
+        "PRESS L TO CHOOSE STARTING LEVEL ({}: {})",
+        start_level + 1,
+        config.levels[start_level].name
+    );
+    let _ = write!(
+        hints[2],
+        "PRESS M TO TOGGLE WRAP-AROUND EDGES ({})",
+        match config.edges {
+            Edges::Solid => "OFF",
+            Edges::Wrap => "ON",
+        }
+    );
+    let _ = write!(
+        hints[3],
+        "PRESS T TO TOGGLE TWO PLAYERS ({})",
+        if config.player_count() > 1 {
+            "ON"
+        } else {
+            "OFF"
+        }
+    );
+    let _ = write!(
+        hints[4],
+        "PRESS C TO CHOOSE COMPUTER OPPONENT ({})",
+        config
+            .opponent
+            .map_or("OFF", |difficulty| difficulty.name())
+    );
+    hints
+}
+
+/// Draw the title screen over the board: the logo, the menu and hints for options the menu leaves out
+pub fn draw_title(game: &Game, menu: &Menu, screen: &Mutex<Writer>) {
+    let logo_character = ScreenChar {
+        ascii_character: 219,
+        color_code: ColorCode::new(Color::Green, Color::Black),
+    };
+    let logo_first_col = (BUFFER_WIDTH - LOGO[0].len()) / 2;
+    clear_board(screen);
+    // Disable interrupts to avoid deadlock
+    interrupts::without_interrupts(|| {
+        let mut writer = screen.lock();
+        for (i, line) in LOGO.iter().enumerate() {
+            for (j, byte) in line.bytes().enumerate() {
+                if byte != b' ' {
+                    writer.write_character_at(logo_character, LOGO_ROW + i, logo_first_col + j);
+                }
+            }
+        }
+    });
+
+    let config = game.config();
+    let mut mode = LineBuffer::new();
+    let _ = match config.opponent {
+        Some(difficulty) if config.player_count() > 1 => {
+            write!(mode, "MODE: VS COMPUTER ({})", difficulty.name())
+        }
+        _ if config.player_count() > 1 => write!(mode, "MODE: 2 PLAYERS"),
+        _ => write!(mode, "MODE: 1 PLAYER"),
+    };
+    let mut speed = LineBuffer::new();
+    let _ = write!(speed, "SPEED: {}", config.start_speed);
+    let mut labels = [""; TITLE_ITEMS.len()];
+    for (label, item) in labels.iter_mut().zip(TITLE_ITEMS.iter()) {
+        *label = match item {
+            TitleItem::Start => "START",
+            TitleItem::Mode => mode.as_str(),
+            TitleItem::Speed => speed.as_str(),
+            TitleItem::HighScores => "HIGH SCORES",
+            TitleItem::Controls => "CONTROLS",
+            TitleItem::Reboot => "REBOOT",
+        };
+    }
+    menu.draw(&labels, MENU_ROW, screen);
+
+    let hints = option_hints(game);
+    for (i, hint) in [MENU_HINT, hints[1].as_str(), hints[2].as_str(), REPLAY_HINT]
+        .iter()
+        .enumerate()
+    {
+        draw_centered(hint, TITLE_HINTS_ROW + i, screen);
+    }
+}
+
+/// Blank every cell of the board, inside the boundary
+fn clear_board(screen: &Mutex<Writer>) {
+    let blank = ScreenChar {
+        ascii_character: b' ',
+        color_code: ColorCode::new(Color::White, Color::Black),
+    };
+    // Disable interrupts to avoid deadlock
+    interrupts::without_interrupts(|| {
+        let mut writer = screen.lock();
+        for row in BOARD_FIRST_ROW..(BOARD_FIRST_ROW + BOARD_HEIGHT) {
+            for col in BOARD_FIRST_COL..(BOARD_FIRST_COL + BOARD_WIDTH) {
+                writer.write_character_at(blank, row, col);
+            }
+        }
+    });
+}
+
+/// Draw a line of text centered on screen at given row
+fn draw_centered(line: &str, row: usize, screen: &Mutex<Writer>) {
+    // Disable interrupts to avoid deadlock
+    interrupts::without_interrupts(|| {
+        screen.lock().write_colored_string_at(
+            line,
+            ColorCode::new(Color::LightGray, Color::Black),
+            row,
+            (BUFFER_WIDTH - line.len()) / 2,
+        );
+    });
+}
+
+/// Lines of the high scores table. Empty places are shown too, so the table keeps its size.
+fn high_score_lines(game: &Game) -> [LineBuffer; TABLE_SIZE] {
+    let mut high_scores = [LineBuffer::new(); TABLE_SIZE];
+    for (i, line) in high_scores.iter_mut().enumerate() {
+        let _ = match game.high_scores().entries().get(i) {
+            Some(entry) => write!(line, "{}. {}  {:>5}", i + 1, entry.initials(), entry.score),
+            None => write!(line, "{}. ---  {:>5}", i + 1, "-"),
+        };
+    }
+    high_scores
+}
+
+/// Draw the high scores table over the board
+pub fn draw_high_scores(game: &Game, screen: &Mutex<Writer>) {
+    let high_scores = high_score_lines(game);
+    draw_banner(
+        &[
+            "HIGH SCORES",
+            "",
+            high_scores[0].as_str(),
+            high_scores[1].as_str(),
+            high_scores[2].as_str(),
+            high_scores[3].as_str(),
+            high_scores[4].as_str(),
+            "",
+            BACK_HINT,
+        ],
+        screen,
+    );
+}
+
+/// Draw the controls page over the board: a menu of the preset and the key bound to each action, with
+/// the selected action's key left out while waiting for a new one
+pub fn draw_controls(bindings: &Bindings, menu: &Menu, rebinding: bool, screen: &Mutex<Writer>) {
+    clear_board(screen);
+    draw_centered("CONTROLS", CONTROLS_ROW, screen);
+
+    let mut lines = [LineBuffer::new(); CONTROLS_ITEMS];
+    for (i, line) in lines.iter_mut().enumerate() {
+        let _ = match game::controls_item(i) {
+            ControlsItem::Preset => write!(
+                line,
+                "PRESET: {}",
+                bindings
+                    .matching_preset()
+                    .map_or("CUSTOM", |preset| preset.name())
+            ),
+            ControlsItem::Binding(action) if rebinding && i == menu.selected() => write!(
+                line,
+                "{:<width$}{:>9}",
+                action.name(),
+                "...",
+                width = ACTION_NAME_WIDTH
+            ),
+            ControlsItem::Binding(action) => write!(
+                line,
+                "{:<width$}{:>9}",
+                action.name(),
+                bindings.key(action),
+                width = ACTION_NAME_WIDTH
+            ),
+            ControlsItem::Back => write!(line, "BACK"),
+        };
+    }
+    let mut labels = [""; CONTROLS_ITEMS];
+    for (label, line) in labels.iter_mut().zip(lines.iter()) {
+        *label = line.as_str();
+    }
+    menu.draw(&labels, CONTROLS_MENU_ROW, screen);
+
+    let mut hint = LineBuffer::new();
+    let _ = match game::controls_item(menu.selected()) {
+        ControlsItem::Binding(action) if rebinding => write!(
+            hint,
+            "PRESS THE KEY FOR {}, OR ESC TO CANCEL",
+            action.name()
+        ),
+        ControlsItem::Preset => write!(hint, "LEFT/RIGHT TO CHANGE PRESET, ESC TO GO BACK"),
+        _ => write!(hint, "{}", CONTROLS_HINT),
+    };
+    draw_centered(hint.as_str(), CONTROLS_HINTS_ROW, screen);
+    draw_centered(OPTION_KEYS_HINT, CONTROLS_HINTS_ROW + 1, screen);
+}
+
+/// Draw a caption over the bottom boundary telling a demo game is being played
+pub fn draw_demo_caption(screen: &Mutex<Writer>) {
+    draw_caption(DEMO_CAPTION, screen);
+}
+
+/// Draw a caption over the bottom boundary telling a replay is being played back, and how fast
+pub fn draw_replay_caption(fast_forward: bool, screen: &Mutex<Writer>) {
+    if fast_forward {
+        draw_caption(FAST_FORWARD_CAPTION, screen);
+    } else {
+        draw_caption(REPLAY_CAPTION, screen);
+    }
+}
+
+/// Draw given caption centered over the bottom boundary
+fn draw_caption(caption: &str, screen: &Mutex<Writer>) {
+    // Disable interrupts to avoid deadlock
+    interrupts::without_interrupts(|| {
+        screen.lock().write_colored_string_at(
+            caption,
+            ColorCode::new(Color::Yellow, Color::Black),
+            CAPTION_ROW,
+            (BUFFER_WIDTH - caption.len()) / 2,
+        );
+    });
+}
+
+/// Draw the banner announcing end of the game with final score and length, or the winner of a
+/// two-player game, followed by either high scores or a prompt for player's initials
+fn draw_game_over(game: &Game, screen: &Mutex<Writer>) {
+    let mut title = LineBuffer::new();
+    let mut stats = LineBuffer::new();
+    if game.players() > 1 {
+        let _ = match game.winner() {
+            Some(player) if game.is_computer(player) => write!(title, "COMPUTER WINS!"),
+            Some(player) => write!(title, "PLAYER {} WINS!", player + 1),
+            None => write!(title, "DRAW"),
+        };
+        for player in 0..game.players() {
+            let _ = write!(
+                stats,
+                "{}{}P SCORE: {}",
+                if player > 0 { "  " } else { "" },
+                player + 1,
+                game.score_of(player).get_score()
+            );
+        }
+    } else {
+        let _ = match game.state() {
+            State::Won => write!(title, "YOU WIN!"),
+            _ => write!(title, "GAME OVER"),
+        };
+        let _ = write!(
+            stats,
+            "SCORE: {}  LENGTH: {}",
+            game.score().get_score(),
+            game.snake().length()
+        );
+    }
+    let title = title.as_str();
+    let mut seed = LineBuffer::new();
+    let _ = write!(seed, "SEED: {}", game.seed());
+    if let Some(initials) = game.initials() {
+        let mut prompt = LineBuffer::new();
+        let _ = write!(prompt, "INITIALS: {:_<3}", initials.as_str());
+        draw_banner(
+            &[
+                title,
+                "",
+                stats.as_str(),
+                seed.as_str(),
+                "",
+                "NEW HIGH SCORE!",
+                prompt.as_str(),
+                "",
+                INITIALS_HINT,
+            ],
+            screen,
+        );
+        return;
+    }
+    let high_scores = high_score_lines(game);
+    let hints = option_hints(game);
+    let mut restart_hint = LineBuffer::new();
+    let _ = write!(
+        restart_hint,
+        "PRESS ENTER OR {} TO PLAY AGAIN",
+        game::bound_key(Action::Restart)
+    );
+    draw_banner(
+        &[
+            title,
+            "",
+            stats.as_str(),
+            seed.as_str(),
+            "",
+            "HIGH SCORES",
+            high_scores[0].as_str(),
+            high_scores[1].as_str(),
+            high_scores[2].as_str(),
+            high_scores[3].as_str(),
+            high_scores[4].as_str(),
+            "",
+            restart_hint.as_str(),
+            hints[0].as_str(),
+            hints[1].as_str(),
+            hints[2].as_str(),
+            hints[3].as_str(),
+            hints[4].as_str(),
+        ],
+        screen,
+    );
+}
+
+/// Draw the game's board along with its boundary and score, and a banner over it when the game is paused or has ended
+pub fn draw(game: &Game, screen: &Mutex<Writer>) {
+    draw_playfield(game, screen);
+    match game.state() {
+        State::Playing => (),
+        State::Paused => {
+            let mut resume_hint = LineBuffer::new();
+            let _ = write!(
+                resume_hint,
+                "PRESS {} OR ESC TO RESUME",
+                game::bound_key(Action::Pause)
+            );
+            draw_banner(&["PAUSED", "", resume_hint.as_str()], screen);
+        }
+        State::GameOver | State::Won => draw_game_over(game, screen),
+    }
+}
+
+/// Draw the game's board along with its boundary, score, speed, seed and level
+pub fn draw_playfield(game: &Game, screen: &Mutex<Writer>) {
+    Boundary {
+        edges: game.board().edges(),
+    }
+    .draw(screen);
+    draw_board(game.board(), screen);
+    draw_player_snakes(game, screen);
+    draw_scores(game, screen);
+    draw_speed(game.speed(), screen);
+    draw_seed(game.seed(), screen);
+    draw_level(game, screen);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vga_buffer::VGA_WRITER;
+    use snake_core::game::Config;
+    use snake_core::snake::MAX_SNAKE_SIZE;
+
+    #[test_case]
+    fn board_is_projected_inside_boundary() {
+        static mut ARRAY: [Position; MAX_SNAKE_SIZE * MAX_PLAYERS] =
+            [Position { row: 0, col: 0 }; MAX_SNAKE_SIZE * MAX_PLAYERS];
+        let mut game = Game::new(1, Config::default(), unsafe { &mut ARRAY });
+        game.step(None);
+        let head = game.snake().head();
+        let food = game.level().food_start_position;
+        // Keep timer interrupt from drawing the global game instead
+        interrupts::without_interrupts(|| {
+            draw(&game, &VGA_WRITER);
+            let writer = VGA_WRITER.lock();
+            let head_character = writer
+                .read_character_at(BOARD_FIRST_ROW + head.row, BOARD_FIRST_COL + head.col);
+            assert_eq!(head_character, *HEAD_LEFT_CHARACTER);
+            let food_character = writer
+                .read_character_at(BOARD_FIRST_ROW + food.row, BOARD_FIRST_COL + food.col);
+            assert_eq!(food_character, *FOOD_CHARACTER);
+        });
+    }
+
+    #[test_case]
+    fn every_food_kind_has_its_own_character() {
+        let kinds = [
+            FoodKind::Regular,
+            FoodKind::Bonus,
+            FoodKind::Slim,
+            FoodKind::Speed,
+        ];
+        for (i, first) in kinds.iter().enumerate() {
+            for second in kinds.iter().skip(i + 1) {
+                assert_ne!(
+                    cell_character(Cell::Food(*first)),
+                    cell_character(Cell::Food(*second))
+                );
+            }
+        }
+    }
+
+    #[test_case]
+    fn two_players_scores_fit_top_row() {
+        static mut ARRAY: [Position; MAX_SNAKE_SIZE * MAX_PLAYERS] =
+            [Position { row: 0, col: 0 }; MAX_SNAKE_SIZE * MAX_PLAYERS];
+        let config = Config {
+            players: 2,
+            ..Config::default()
+        };
+        let game = Game::new(1, config, unsafe { &mut ARRAY });
+        interrupts::without_interrupts(|| {
+            draw_scores(&game, &VGA_WRITER);
+            let writer = VGA_WRITER.lock();
+            let first = writer.read_character_at(PLAYER_SCORES_ROW, PLAYER_SCORES_COL);
+            assert_eq!(first.ascii_character, b'1');
+            let last = writer.read_character_at(PLAYER_SCORES_ROW, BUFFER_WIDTH - 1);
+            assert_eq!(last.ascii_character, b'0');
+        });
+    }
+
+    #[test_case]
+    fn demo_caption_is_centered_on_bottom_boundary() {
+        interrupts::without_interrupts(|| {
+            draw_demo_caption(&VGA_WRITER);
+            let writer = VGA_WRITER.lock();
+            let first_col = (BUFFER_WIDTH - DEMO_CAPTION.len()) / 2;
+            for (i, byte) in DEMO_CAPTION.bytes().enumerate() {
+                let character = writer.read_character_at(CAPTION_ROW, first_col + i);
+                assert_eq!(character.ascii_character, byte);
+            }
+        });
+    }
+
+    #[test_case]
+    fn title_screen_fits_board() {
+        for line in LOGO.iter() {
+            assert_eq!(line.len(), LOGO[0].len());
+        }
+        assert!(MENU_HINT.len() <= BOARD_WIDTH);
+        // Menu hint, level, edges and replay hints
+        assert!(TITLE_HINTS_ROW + 4 <= BOARD_FIRST_ROW + BOARD_HEIGHT);
+    }
+
+    #[test_case]
+    fn controls_page_fits_board() {
+        assert!(OPTION_KEYS_HINT.len() <= BOARD_WIDTH);
+        // Hint of the selected item and option keys
+        assert!(CONTROLS_HINTS_ROW + 2 <= BOARD_FIRST_ROW + BOARD_HEIGHT);
+    }
+
+    #[test_case]
+    fn score_is_right_aligned() {
+        let mut score = Score::new(0);
+        for _ in 0..12 {
+            score.increment();
+        }
+        interrupts::without_interrupts(|| {
+            draw_score(&score, &VGA_WRITER);
+            let writer = VGA_WRITER.lock();
+            assert_eq!(
+                writer.read_character_at(SCORE_ROW, BUFFER_WIDTH - 2).ascii_character,
+                b'1'
+            );
+            assert_eq!(
+                writer.read_character_at(SCORE_ROW, BUFFER_WIDTH - 1).ascii_character,
+                b'2'
+            );
+            assert_eq!(
+                writer.read_character_at(SCORE_ROW, BUFFER_WIDTH - 3).ascii_character,
+                b' '
+            );
+        });
+    }
+}
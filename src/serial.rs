@@ -0,0 +1,60 @@
+/* Module for writing text to serial port COM1
+//
+// With `-serial stdio` in QEMU, anything written here shows up on the host's
+// terminal without disturbing the play field on screen.
+// Significant portion of code has been used from Philipp Oppermann's blog
+// @ https://os.phil-opp.com/
+// Copyright (c) 2019 Philipp Oppermann
+*/
+
+use core::fmt;
+use core::sync::atomic::{AtomicBool, Ordering};
+use lazy_static::lazy_static;
+use spin::Mutex;
+use uart_16550::SerialPort;
+
+// I/O port of first serial port
+const COM1_PORT: u16 = 0x3F8;
+
+// Whether anything is written to serial port, which can be turned off on the command line
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+lazy_static! {
+    /// Static SerialPort instance to use for writing to COM1
+    pub static ref SERIAL1: Mutex<SerialPort> = {
+        let mut serial_port = unsafe { SerialPort::new(COM1_PORT) };
+        serial_port.init();
+        Mutex::new(serial_port)
+    };
+}
+
+/// Print string to serial port
+#[macro_export]
+macro_rules! serial_print {
+    ($($arg:tt)*) => ($crate::serial::_print(format_args!($($arg)*)));
+}
+
+/// Print string to serial port, and change line after it
+#[macro_export]
+macro_rules! serial_println {
+    () => ($crate::serial_print!("\n"));
+    ($($arg:tt)*) => ($crate::serial_print!("{}\n", format_args!($($arg)*)));
+}
+
+/// Turn writing to serial port on or off
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+#[doc(hidden)]
+pub fn _print(args: fmt::Arguments) {
+    use core::fmt::Write;
+    use x86_64::instructions::interrupts;
+
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    interrupts::without_interrupts(|| {
+        SERIAL1.lock().write_fmt(args).unwrap();
+    });
+}
@@ -0,0 +1,140 @@
+/* Module saving records to the ATA drive and reading them back
+//
+// Each kind of record has an area of the disk to itself, as laid out by
+// `snake_core::record`. Records are moved through a single sector buffer, so
+// a payload never has to sit on the stack in one piece along with its header.
+//
+// Records are saved a sector at a time with interrupts disabled, so that a
+// long record saved outside of interrupt handlers holds them off for no more
+// than a sector. Handlers may save records of other kinds in between, which
+// lie in other areas of the disk.
+*/
+
+use snake_core::record::{Header, RecordError, RecordKind, HEADER_SIZE, SECTOR_SIZE};
+use x86_64::instructions::interrupts;
+
+use crate::ata::{AtaError, PRIMARY_DRIVE};
+
+/// Reasons a record cannot be saved or loaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageError {
+    /// No drive is attached
+    NoDisk,
+    /// Transfer with the drive failed
+    Disk(AtaError),
+    /// Record is too long, or what is on the disk is not a valid record
+    Record(RecordError),
+}
+
+impl From<AtaError> for StorageError {
+    fn from(error: AtaError) -> Self {
+        StorageError::Disk(error)
+    }
+}
+
+impl From<RecordError> for StorageError {
+    fn from(error: RecordError) -> Self {
+        StorageError::Record(error)
+    }
+}
+
+/// Check if there is a drive to keep records on
+pub fn is_available() -> bool {
+    // Disable interrupts to avoid deadlock
+    interrupts::without_interrupts(|| PRIMARY_DRIVE.lock().is_some())
+}
+
+/// Save payload as the record of given kind, replacing the one saved before
+pub fn save(kind: RecordKind, version: u8, payload: &[u8]) -> Result<(), StorageError> {
+    let header = Header::new(kind, version, payload)?.encode();
+    let length = HEADER_SIZE + payload.len();
+    let mut sector = [0; SECTOR_SIZE];
+    let mut offset = 0;
+    let mut lba = kind.first_sector();
+    while offset < length {
+        // Header and payload follow each other across sector boundaries
+        for (i, byte) in sector.iter_mut().enumerate() {
+            let position = offset + i;
+            *byte = if position < HEADER_SIZE {
+                header[position]
+            } else if position < length {
+                payload[position - HEADER_SIZE]
+            } else {
+                0
+            };
+        }
+        // Disable interrupts to avoid deadlock
+        interrupts::without_interrupts(|| -> Result<(), StorageError> {
+            let mut drive = PRIMARY_DRIVE.lock();
+            let drive = drive.as_mut().ok_or(StorageError::NoDisk)?;
+            drive.write_sector(lba, &sector)?;
+            Ok(())
+        })?;
+        offset += SECTOR_SIZE;
+        lba += 1;
+    }
+    Ok(())
+}
+
+/// Read the record of given kind into buffer. Return version of the record and length of its payload.
+pub fn load(kind: RecordKind, buffer: &mut [u8]) -> Result<(u8, usize), StorageError> {
+    // Disable interrupts to avoid deadlock
+    interrupts::without_interrupts(|| {
+        let mut drive = PRIMARY_DRIVE.lock();
+        let drive = drive.as_mut().ok_or(StorageError::NoDisk)?;
+        let mut sector = [0; SECTOR_SIZE];
+        let mut lba = kind.first_sector();
+        drive.read_sector(lba, &mut sector)?;
+        let mut header_bytes = [0; HEADER_SIZE];
+        header_bytes.copy_from_slice(&sector[..HEADER_SIZE]);
+        let header = Header::decode(kind, &header_bytes)?;
+        let length = header.length as usize;
+        if length > buffer.len() {
+            return Err(StorageError::Record(RecordError::TooLong));
+        }
+        let mut offset = 0;
+        let mut sector_offset = HEADER_SIZE;
+        while offset < length {
+            if sector_offset == SECTOR_SIZE {
+                lba += 1;
+                drive.read_sector(lba, &mut sector)?;
+                sector_offset = 0;
+            }
+            let count = (SECTOR_SIZE - sector_offset).min(length - offset);
+            buffer[offset..(offset + count)]
+                .copy_from_slice(&sector[sector_offset..(sector_offset + count)]);
+            offset += count;
+            sector_offset += count;
+        }
+        header.verify(&buffer[..length])?;
+        Ok((header.version, length))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_case]
+    fn record_spanning_sectors_is_read_back() {
+        // `make test` attaches a scratch disk
+        assert!(is_available());
+        let mut payload = [0; 2 * SECTOR_SIZE];
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte = (i % 251) as u8;
+        }
+        save(RecordKind::Replay, 7, &payload).unwrap();
+        let mut buffer = [0; 2 * SECTOR_SIZE];
+        assert_eq!(
+            load(RecordKind::Replay, &mut buffer),
+            Ok((7, payload.len()))
+        );
+        assert!(buffer.iter().zip(payload.iter()).all(|(a, b)| a == b));
+
+        let mut short_buffer = [0; SECTOR_SIZE];
+        assert_eq!(
+            load(RecordKind::Replay, &mut short_buffer),
+            Err(StorageError::Record(RecordError::TooLong))
+        );
+    }
+}
@@ -5,8 +5,8 @@
 
 use x86_64::instructions::port::Port;
 
-const CMOS_ADDRESS_PORT: u16 = 0x70;
-const CMOS_DATA_PORT: u16 = 0x71;
+pub const CMOS_ADDRESS_PORT: u16 = 0x70;
+pub const CMOS_DATA_PORT: u16 = 0x71;
 const STATUS_REGISTER_A: u8 = 0x0A;
 const STATUS_REGISTER_B: u8 = 0x0B;
 const SECOND_REGISTER: u8 = 0x00;
@@ -14,7 +14,46 @@ const MINUTE_REGISTER: u8 = 0x02;
 const HOUR_REGISTER: u8 = 0x04;
 const DAY_REGISTER: u8 = 0x07;
 const MONTH_REGISTER: u8 = 0x08;
-//const YEAR_REGISTER: u8 = 0x09;
+const YEAR_REGISTER: u8 = 0x09;
+// RTC only keeps the last two digits of the year
+const CENTURY: u64 = 2000;
+// Days before the first of each month in a year that is not a leap year
+const DAYS_BEFORE_MONTH: [u64; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+
+/// Date and time read from RTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTime {
+    pub year: u64,
+    /// Month of the year, from 1 to 12
+    pub month: u64,
+    /// Day of the month, from 1 to 31
+    pub day: u64,
+    /// Hour of the day, from 0 to 23
+    pub hour: u64,
+    pub minute: u64,
+    pub second: u64,
+}
+
+impl DateTime {
+    /// Number of seconds passed since the start of the century, counting leap years
+    pub fn seconds_since_century(&self) -> u64 {
+        let years = self.year - CENTURY;
+        // Every fourth year of the century is a leap year, including the first one
+        let mut days = years * 365 + (years + 3) / 4;
+        days += DAYS_BEFORE_MONTH[(self.month as usize + 11) % 12];
+        if years % 4 == 0 && self.month > 2 {
+            days += 1;
+        }
+        // Garbage in RTC registers must not overflow
+        days += self.day.saturating_sub(1);
+        ((days * 24 + self.hour) * 60 + self.minute) * 60 + self.second
+    }
+
+    /// Date written as the decimal number YYYYMMDD
+    pub fn date_number(&self) -> u64 {
+        (self.year * 100 + self.month) * 100 + self.day
+    }
+}
 
 /// Read status register for 'Update in progress'
 fn get_update_in_progress_flag(
@@ -37,47 +76,97 @@ fn get_rtc_register(
     value
 }
 
-// We do not need exact system time, so this is not a proper
-// implementation to get system time, and we are okay with
-// inconsistent value
-/// Get some seed from system time.
-pub fn get_system_time_seed() -> u64 {
+/// Read date and time registers, as stored by RTC, once no update is in progress
+fn read_raw_date_time(cmos_address_port: &mut Port<u8>, cmos_data_port: &mut Port<u8>) -> DateTime {
+    while get_update_in_progress_flag(cmos_address_port, cmos_data_port) != 0 {}
+    DateTime {
+        year: get_rtc_register(cmos_address_port, cmos_data_port, YEAR_REGISTER) as u64,
+        month: get_rtc_register(cmos_address_port, cmos_data_port, MONTH_REGISTER) as u64,
+        day: get_rtc_register(cmos_address_port, cmos_data_port, DAY_REGISTER) as u64,
+        hour: get_rtc_register(cmos_address_port, cmos_data_port, HOUR_REGISTER) as u64,
+        minute: get_rtc_register(cmos_address_port, cmos_data_port, MINUTE_REGISTER) as u64,
+        second: get_rtc_register(cmos_address_port, cmos_data_port, SECOND_REGISTER) as u64,
+    }
+}
+
+/// Convert a value stored by RTC in binary-coded decimal
+fn from_bcd(value: u64) -> u64 {
+    (value & 0x0F) + ((value / 16) * 10)
+}
+
+/// Read the current date and time from RTC
+pub fn read_date_time() -> DateTime {
     let mut cmos_address_port = Port::new(CMOS_ADDRESS_PORT);
     let mut cmos_data_port = Port::new(CMOS_DATA_PORT);
-    let mut second: u64 = 0;
-    let mut minute: u64 = 0;
-    let mut hour: u64 = 0;
-    let mut day: u64 = 0;
-    let mut month: u64 = 0;
-    //let mut year: u8 = 0;
-    while get_update_in_progress_flag(&mut cmos_address_port, &mut cmos_data_port) != 0 {
-        second =
-            get_rtc_register(&mut cmos_address_port, &mut cmos_data_port, SECOND_REGISTER) as u64;
-        minute =
-            get_rtc_register(&mut cmos_address_port, &mut cmos_data_port, MINUTE_REGISTER) as u64;
-        hour = get_rtc_register(&mut cmos_address_port, &mut cmos_data_port, HOUR_REGISTER) as u64;
-        day = get_rtc_register(&mut cmos_address_port, &mut cmos_data_port, DAY_REGISTER) as u64;
-        month =
-            get_rtc_register(&mut cmos_address_port, &mut cmos_data_port, MONTH_REGISTER) as u64;
-        //year = get_rtc_register(&mut cmos_address_port, &mut cmos_data_port, YEAR_REGISTER) as u64;
+    // Read until the same values come back twice in a row, so an update
+    // starting in the middle of reading cannot leave fields inconsistent
+    let mut date_time = read_raw_date_time(&mut cmos_address_port, &mut cmos_data_port);
+    loop {
+        let again = read_raw_date_time(&mut cmos_address_port, &mut cmos_data_port);
+        if again == date_time {
+            break;
+        }
+        date_time = again;
     }
     let register_b_status = get_rtc_register(
         &mut cmos_address_port,
         &mut cmos_data_port,
         STATUS_REGISTER_B,
     );
+    let mut hour = date_time.hour;
     if (register_b_status & 0x04) == 0 {
-        second = (second & 0x0F) + ((second / 16) * 10);
-        minute = (minute & 0x0F) + ((minute / 16) * 10);
-        hour = ((hour & 0x0F) + (((hour & 0x70) / 16) * 10)) | (hour & 0x80);
-        day = (day & 0x0F) + ((day / 16) * 10);
-        month = (month & 0x0F) + ((month / 16) * 10);
-        //year = (year & 0x0F) + ((year / 16) * 10);
+        date_time.second = from_bcd(date_time.second);
+        date_time.minute = from_bcd(date_time.minute);
+        hour = from_bcd(hour & 0x7F) | (hour & 0x80);
+        date_time.day = from_bcd(date_time.day);
+        date_time.month = from_bcd(date_time.month);
+        date_time.year = from_bcd(date_time.year);
+    }
+    // In 12 hour format, the highest bit marks hours after noon, and midnight and noon are 12
+    if (register_b_status & 0x02) == 0 {
+        let after_noon = (hour & 0x80) != 0;
+        hour = (hour & 0x7F) % 12 + if after_noon { 12 } else { 0 };
+    }
+    date_time.hour = hour;
+    date_time.year += CENTURY;
+    date_time
+}
+
+/// Get a seed from system time, different for every second
+pub fn get_system_time_seed() -> u64 {
+    read_date_time().seconds_since_century()
+}
+
+/// Get a seed from today's date, the same for the whole day
+pub fn get_daily_seed() -> u64 {
+    read_date_time().date_number()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date_time(year: u64, month: u64, day: u64) -> DateTime {
+        DateTime {
+            year,
+            month,
+            day,
+            hour: 0,
+            minute: 0,
+            second: 0,
+        }
     }
 
-    if (register_b_status & 0x02) == 0 && (hour & 0x80) != 0 {
-        hour = ((hour & 0x7F) + 12) % 24;
+    #[test_case]
+    fn seconds_count_leap_days() {
+        assert_eq!(date_time(2000, 1, 1).seconds_since_century(), 0);
+        // 2000 is a leap year, so March starts 60 days in
+        assert_eq!(date_time(2000, 3, 1).seconds_since_century(), 60 * 86_400);
+        // 2000 through 2003 hold a single leap day
+        assert_eq!(
+            date_time(2004, 1, 1).seconds_since_century(),
+            (4 * 365 + 1) * 86_400
+        );
+        assert_eq!(date_time(2020, 2, 14).date_number(), 20_200_214);
     }
-    // Naive usage of 30 days month
-    second + (minute * 60) + (hour * 3600) + (day * 86400) + (month * 2_592_000)
 }
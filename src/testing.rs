@@ -0,0 +1,105 @@
+/* Test framework running test cases inside QEMU
+//
+// Test results are reported over serial port, and QEMU is shut down with an
+// exit code telling if all tests passed, through the `isa-debug-exit` device.
+// Significant portion of code has been used from Philipp Oppermann's blog
+// @ https://os.phil-opp.com/
+// Copyright (c) 2019 Philipp Oppermann
+*/
+
+use core::panic::PanicInfo;
+use x86_64::instructions::port::Port;
+
+use crate::{hlt_loop, serial_print, serial_println};
+
+// I/O port of `isa-debug-exit` device, as configured on QEMU command line
+const ISA_DEBUG_EXIT_PORT: u16 = 0xf4;
+
+/// Exit codes written to `isa-debug-exit` device. QEMU exits with `(code << 1) | 1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum QemuExitCode {
+    Success = 0x10,
+    Failed = 0x11,
+}
+
+/// Shut down QEMU with given exit code
+pub fn exit_qemu(exit_code: QemuExitCode) {
+    let mut port = Port::new(ISA_DEBUG_EXIT_PORT);
+    unsafe { port.write(exit_code as u32) };
+}
+
+/// Test case which reports its name and result
+pub trait Testable {
+    fn run(&self);
+}
+
+impl<T: Fn()> Testable for T {
+    fn run(&self) {
+        serial_print!("{}...\t", core::any::type_name::<T>());
+        self();
+        serial_println!("[ok]");
+    }
+}
+
+/// Run all test cases registered with `#[test_case]`, and exit QEMU once all of them pass
+pub fn test_runner(tests: &[&dyn Testable]) {
+    serial_println!("Running {} tests", tests.len());
+    for test in tests {
+        test.run();
+    }
+    exit_qemu(QemuExitCode::Success);
+}
+
+/// Report the failing test case, and exit QEMU
+pub fn test_panic_handler(info: &PanicInfo) -> ! {
+    serial_println!("[failed]\n");
+    serial_println!("Error: {}\n", info);
+    exit_qemu(QemuExitCode::Failed);
+    hlt_loop();
+}
+
+// Sanity checks for snake_core compiled for the kernel target, which has no SSE and no red zone
+mod tests {
+    use snake_core::board::{Cell, Position};
+    use snake_core::game::{Config, Game, MAX_PLAYERS};
+    use snake_core::ring_buffer::RingBuffer;
+    use snake_core::score::Score;
+    use snake_core::snake::{Direction, MAX_SNAKE_SIZE};
+
+    #[test_case]
+    fn ring_buffer_over_static_array() {
+        static mut ARRAY: [u64; 4] = [0; 4];
+        let mut ring_buffer = RingBuffer::new(unsafe { &mut ARRAY[..] });
+        for i in 0..10 {
+            ring_buffer.prepend(i);
+            if ring_buffer.is_full() {
+                assert_eq!(*ring_buffer.pop_last(), i - 3);
+            }
+        }
+        assert_eq!(*ring_buffer.peek_first(), 9);
+        assert_eq!(ring_buffer.len(), 4);
+    }
+
+    #[test_case]
+    fn score_increments() {
+        let mut score = Score::new(0);
+        score.increment();
+        score.increment();
+        assert_eq!(score.get_score(), 2);
+    }
+
+    #[test_case]
+    fn snake_moves_and_turns() {
+        static mut ARRAY: [Position; MAX_SNAKE_SIZE * MAX_PLAYERS] =
+            [Position { row: 0, col: 0 }; MAX_SNAKE_SIZE * MAX_PLAYERS];
+        let mut game = Game::new(1, Config::default(), unsafe { &mut ARRAY });
+        let start = game.snake().head();
+        game.step(None);
+        game.step(Some(Direction::Up));
+        let head = game.snake().head();
+        assert_eq!(head.row, start.row - 1);
+        assert_eq!(head.col, start.col - 1);
+        assert_eq!(game.board().get(head), Cell::Head(Direction::Up));
+    }
+}
@@ -164,6 +164,18 @@ impl Writer {
 
     /// Write a string starting at given row and column in VGA buffer
     pub fn write_string_at(&mut self, string: &str, row: usize, col: usize) {
+        let color_code = self.color_code;
+        self.write_colored_string_at(string, color_code, row, col);
+    }
+
+    /// Write a string in given color starting at given row and column in VGA buffer
+    pub fn write_colored_string_at(
+        &mut self,
+        string: &str,
+        color_code: ColorCode,
+        row: usize,
+        col: usize,
+    ) {
         let mut current_row = row;
         let mut current_col = col;
         for c in string.chars() {
@@ -179,7 +191,7 @@ impl Writer {
                     }
                     self.buffer.chars[current_row][current_col].write(ScreenChar {
                         ascii_character: c as u8,
-                        color_code: self.color_code,
+                        color_code,
                     });
                     current_col += 1;
                 }
@@ -195,6 +207,46 @@ impl fmt::Write for Writer {
     }
 }
 
+/// Fixed size stack buffer to format a single line of text, so that redrawing from the timer interrupt on every tick does not walk the heap free list
+#[derive(Clone, Copy)]
+pub struct LineBuffer {
+    bytes: [u8; BUFFER_WIDTH],
+    len: usize,
+}
+
+impl LineBuffer {
+    pub fn new() -> Self {
+        LineBuffer {
+            bytes: [0; BUFFER_WIDTH],
+            len: 0,
+        }
+    }
+
+    /// Get the text formatted so far
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap_or("")
+    }
+}
+
+impl Default for LineBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Write for LineBuffer {
+    /// Append string to the line, failing if it does not fit on the screen
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > BUFFER_WIDTH {
+            return Err(fmt::Error);
+        }
+        self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
 /// Print string at end of VGA buffer
 #[macro_export]
 macro_rules! print {
@@ -217,3 +269,35 @@ pub fn _print(args: fmt::Arguments) {
         VGA_WRITER.lock().write_fmt(args).unwrap();
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+    use x86_64::instructions::interrupts;
+
+    #[test_case]
+    fn write_string_at_reads_back() {
+        // Keep timer interrupt from drawing the game over the text
+        interrupts::without_interrupts(|| {
+            let mut writer = VGA_WRITER.lock();
+            writer.write_string_at("SNAKE", 5, 10);
+            for (i, c) in "SNAKE".bytes().enumerate() {
+                assert_eq!(writer.read_character_at(5, 10 + i).ascii_character, c);
+            }
+        });
+    }
+
+    #[test_case]
+    fn line_buffer_formats_within_screen_width() {
+        let mut line = LineBuffer::new();
+        write!(line, "SCORE: {:>5}", 42).unwrap();
+        assert_eq!(line.as_str(), "SCORE:    42");
+        let mut line = LineBuffer::new();
+        for _ in 0..BUFFER_WIDTH {
+            write!(line, "x").unwrap();
+        }
+        assert!(write!(line, "x").is_err());
+        assert_eq!(line.as_str().len(), BUFFER_WIDTH);
+    }
+}